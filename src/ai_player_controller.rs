@@ -1,11 +1,13 @@
 use crate::{
     animation::inverse_lerp,
-    ball::{Ball, BallBounce, BallHitEvt, BALL_MAX_SPEED},
+    ball::{Ball, BallBounce, BallHitEvt, BallStatus, BALL_MAX_SPEED},
+    ends::Ends,
     input_binding::{InputAction, InputAxis, PlayerInput},
-    level::{InitialRegion, NetOffset},
+    level::{CourtSettings, InitialRegion, NetOffset},
+    match_config::{GameMode, MatchConfig},
     player::{
-        get_swing_multiplier_clamped, spawn_player, Player, PlayerAim, PlayerDash, PlayerMovement,
-        PlayerSwing, SWING_LABEL,
+        get_swing_multiplier_clamped, spawn_player, Player, PlayerDash, PlayerHandedness,
+        PlayerMovement, PlayerSwing, SwingConfig, SWING_LABEL,
     },
     player_action::PlayerActionStatus,
     GameState,
@@ -13,17 +15,63 @@ use crate::{
 use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
 use big_brain::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+// ~2s worth of frames
+const OPPONENT_HISTORY_LEN: usize = 120;
+// how far towards the top/bottom line the AI tries to place the ball
+const WEAK_SIDE_TARGET_Y: f32 = 0.7;
 
 pub struct AiPlayerControllerPlugin;
 impl Plugin for AiPlayerControllerPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_system_set(SystemSet::on_enter(GameState::Game).with_system(setup))
-            .add_system_set(SystemSet::on_update(GameState::Game).with_system(collect_inputs))
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(collect_inputs)
+                    .with_system(track_opponents)
+                    .with_system(target_weak_side),
+            )
             .add_system_to_stage(BigBrainStage::Actions, stand_still)
             .add_system_to_stage(BigBrainStage::Scorers, score_move_to_ball)
             .add_system_to_stage(BigBrainStage::Actions, move_to_ball_action)
             .add_system_to_stage(BigBrainStage::Scorers, score_swing)
-            .add_system_to_stage(BigBrainStage::Actions, swing_action);
+            .add_system_to_stage(BigBrainStage::Actions, swing_action)
+            .init_resource::<AiDifficulty>();
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum AiDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    // runs the opponent around by aiming away from where they tend to stand
+    Hard,
+}
+
+impl AiDifficulty {
+    pub fn next(&self) -> Self {
+        match self {
+            AiDifficulty::Easy => AiDifficulty::Normal,
+            AiDifficulty::Normal => AiDifficulty::Hard,
+            AiDifficulty::Hard => AiDifficulty::Easy,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            AiDifficulty::Easy => "Easy",
+            AiDifficulty::Normal => "Normal",
+            AiDifficulty::Hard => "Hard",
+        }
+    }
+
+    fn get_move_mult(&self) -> f32 {
+        match self {
+            AiDifficulty::Easy => 0.75,
+            AiDifficulty::Normal | AiDifficulty::Hard => 1.,
+        }
     }
 }
 
@@ -41,6 +89,68 @@ pub struct AiPlayerInputs {
     closest_incoming_ball: Option<BallData>,
 }
 
+// recent positions of the opposing human players by player id
+#[derive(Component, Default)]
+pub struct OpponentHistory {
+    positions: HashMap<usize, VecDeque<Vec2>>,
+}
+
+impl OpponentHistory {
+    fn push(&mut self, player_id: usize, pos: Vec2) {
+        let positions = self.positions.entry(player_id).or_default();
+        positions.push_back(pos);
+
+        while positions.len() > OPPONENT_HISTORY_LEN {
+            positions.pop_front();
+        }
+    }
+
+    fn get_average(&self, player_id: usize) -> Option<Vec2> {
+        self.positions
+            .get(&player_id)
+            .filter(|positions| !positions.is_empty())
+            .map(|positions| positions.iter().sum::<Vec2>() / positions.len() as f32)
+    }
+
+    fn get_averages(&self) -> Vec<Vec2> {
+        self.positions
+            .keys()
+            .filter_map(|id| self.get_average(*id))
+            .collect()
+    }
+}
+
+// where the hard AI aims its next shot, applied on hit within the usual aim clamp
+#[derive(Component, Default)]
+pub struct AiAimTarget(pub Option<Vec2>);
+
+// the top or bottom side that's the furthest from all of the opponents
+fn get_weak_side_target(
+    opponent_avgs: &[Vec2],
+    sign: f32,
+    court_set: &CourtSettings,
+) -> Option<Vec2> {
+    if opponent_avgs.is_empty() {
+        return None;
+    }
+
+    let x = -sign * court_set.right / 2.;
+    let dist_to_nearest = |target: Vec2| {
+        opponent_avgs
+            .iter()
+            .map(|avg| avg.distance_squared(target))
+            .fold(f32::MAX, f32::min)
+    };
+    let top = Vec2::new(x, court_set.top * WEAK_SIDE_TARGET_Y);
+    let bottom = Vec2::new(x, court_set.bottom * WEAK_SIDE_TARGET_Y);
+
+    Some(if dist_to_nearest(top) >= dist_to_nearest(bottom) {
+        top
+    } else {
+        bottom
+    })
+}
+
 #[derive(Debug, Clone, Component)]
 pub struct StandStillAction;
 
@@ -80,8 +190,17 @@ pub struct SwingAction;
 // swing thinker
 // dodge thinker
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>, region: Res<InitialRegion>) {
-    if cfg!(feature = "debug") {
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    region: Res<InitialRegion>,
+    handedness: Res<PlayerHandedness>,
+    swing_config: Res<SwingConfig>,
+    config: Res<MatchConfig>,
+    game_mode: Res<GameMode>,
+    ends: Res<Ends>,
+) {
+    for id in (1..=config.get_player_count()).filter(|id| game_mode.is_ai_player(*id)) {
         let move_thinker = Thinker::build()
             .picker(FirstToScore::new(0.2))
             .when(MoveToBallScorer, MoveToBallAction)
@@ -95,13 +214,24 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, region: Res<Ini
             .picker(FirstToScore::new(0.2))
             .when(SwingScorer, SwingAction);
 
-        spawn_player(2, &mut commands, &asset_server, &region)
-            .insert(AiPlayerInputs::default())
-            .insert(AiPlayer)
-            .insert(move_thinker)
-            .with_children(|b| {
-                b.spawn().insert(swing_thinker);
-            });
+        spawn_player(
+            id,
+            &mut commands,
+            &asset_server,
+            &region,
+            &handedness,
+            &swing_config,
+            &config,
+            &ends,
+        )
+        .insert(AiPlayerInputs::default())
+        .insert(OpponentHistory::default())
+        .insert(AiAimTarget::default())
+        .insert(AiPlayer)
+        .insert(move_thinker)
+        .with_children(|b| {
+            b.spawn().insert(swing_thinker);
+        });
     }
 }
 
@@ -127,15 +257,18 @@ fn on_ball_hit(
 
 fn collect_inputs(
     mut ai_q: Query<(&mut AiPlayerInputs, &GlobalTransform, &Player), With<AiPlayer>>,
-    ball_q: Query<(Entity, &Ball, &GlobalTransform), Without<AiPlayer>>,
+    ball_q: Query<(Entity, &Ball, &BallStatus, &GlobalTransform), Without<AiPlayer>>,
 ) {
     for (mut inputs, ai_t, player) in ai_q.iter_mut() {
-        if let Some((e, ball, ball_t)) = ball_q
+        if let Some((e, _, _, ball_t)) = ball_q
             .iter()
-            .filter(|(_, b, _)| {
-                (player.is_left() && b.dir.x < 0.) || (!player.is_left() && b.dir.x > 0.)
+            .filter(|(_, b, status, _)| {
+                // the ball waiting for the AI's serve is not moving yet
+                matches!(status, BallStatus::Serve(_, _, id) if *id == player.id)
+                    || (player.is_left() && b.dir.x < 0.)
+                    || (!player.is_left() && b.dir.x > 0.)
             })
-            .max_by(|(_, _, t1), (_, _, t2)| {
+            .max_by(|(_, _, _, t1), (_, _, _, t2)| {
                 if player.is_left() {
                     t1.translation.x.partial_cmp(&t2.translation.x).unwrap()
                 } else {
@@ -153,6 +286,35 @@ fn collect_inputs(
     }
 }
 
+fn track_opponents(
+    mut ai_q: Query<(&mut OpponentHistory, &Player), With<AiPlayer>>,
+    opponent_q: Query<(&Player, &GlobalTransform), Without<AiPlayer>>,
+) {
+    for (mut history, ai) in ai_q.iter_mut() {
+        for (p, t) in opponent_q
+            .iter()
+            .filter(|(p, _)| p.is_left() != ai.is_left())
+        {
+            history.push(p.id, t.translation.truncate());
+        }
+    }
+}
+
+// the ball hit takes care of the clamping, same as for the human players
+fn target_weak_side(
+    mut ai_q: Query<(&mut AiAimTarget, &OpponentHistory, &Player), With<AiPlayer>>,
+    difficulty: Res<AiDifficulty>,
+    court_set: Res<CourtSettings>,
+) {
+    for (mut target, history, ai) in ai_q.iter_mut() {
+        target.0 = if *difficulty == AiDifficulty::Hard {
+            get_weak_side_target(&history.get_averages(), ai.get_sign(), &court_set)
+        } else {
+            None
+        };
+    }
+}
+
 fn stand_still(
     mut action_q: Query<(&Actor, &mut ActionState), With<StandStillAction>>,
     mut move_q: Query<&mut PlayerMovement>,
@@ -221,6 +383,7 @@ fn move_to_ball_action(
     mut action_q: Query<(&Actor, &mut ActionState), With<MoveToBallAction>>,
     mut q: Query<(&mut PlayerMovement, &AiPlayerInputs, &GlobalTransform)>,
     ball_q: Query<&GlobalTransform, (With<Ball>, Without<Player>)>,
+    difficulty: Res<AiDifficulty>,
 ) {
     for (Actor(actor), mut state) in action_q.iter_mut() {
         if let Ok((mut movement, inputs, t)) = q.get_mut(*actor) {
@@ -237,7 +400,8 @@ fn move_to_ball_action(
                                 );
                                 movement.raw_dir =
                                     (ball_t.translation - t.translation).truncate().normalize()
-                                        * dist_mult;
+                                        * dist_mult
+                                        * difficulty.get_move_mult();
                             }
                         }
                         None => movement.raw_dir = Vec2::ZERO,
@@ -307,3 +471,42 @@ fn swing_action(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_is_kept_per_opponent() {
+        let mut history = OpponentHistory::default();
+
+        for _ in 0..OPPONENT_HISTORY_LEN {
+            history.push(1, Vec2::new(-100., 50.));
+        }
+        history.push(3, Vec2::new(-100., -50.));
+
+        assert_eq!(history.get_average(1), Some(Vec2::new(-100., 50.)));
+        assert_eq!(history.get_average(3), Some(Vec2::new(-100., -50.)));
+        assert_eq!(history.get_average(2), None);
+        assert_eq!(history.positions[&1].len(), OPPONENT_HISTORY_LEN);
+    }
+
+    #[test]
+    fn weak_side_is_away_from_the_opponent() {
+        let court_set = CourtSettings {
+            left: -550.,
+            right: 550.,
+            top: 300.,
+            bottom: -300.,
+            base_region_size: Vec3::ONE,
+            region_x: 275.,
+        };
+        // the AI on the right, the opponent camping the top of the left side
+        let target =
+            get_weak_side_target(&[Vec2::new(-100., court_set.top)], 1., &court_set).unwrap();
+
+        assert!(target.x < 0.);
+        assert_eq!(target.y, court_set.bottom * WEAK_SIDE_TARGET_Y);
+        assert!(get_weak_side_target(&[], 1., &court_set).is_none());
+    }
+}
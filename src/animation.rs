@@ -1,12 +1,37 @@
+use crate::{game_speed::GameTime, GameState};
 use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
-use bevy_time::{ScaledTime, ScaledTimeDelta};
-use bevy_tweening::TweenCompleted;
+use bevy_tweening::{Animator, AnimatorState, TweenCompleted};
 
 pub struct AnimationPlugin;
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_system(rotate).add_system(on_tween_completed);
+        // replaces the TweeningPlugin which ticks the animators on unscaled time
+        // needs a system per animated component, these are all the lens targets in use
+        app.add_event::<TweenCompleted>()
+            .add_system(scaled_animator_system::<Transform>)
+            .add_system(scaled_animator_system::<Sprite>)
+            .add_system(scaled_animator_system::<Text>)
+            .add_system(rotate)
+            .add_system(on_tween_completed)
+            .init_resource::<ReduceMotion>();
+    }
+}
+
+// accessibility - no spinning, no shake & toned down squash/stretch
+#[derive(Default)]
+pub struct ReduceMotion {
+    pub enabled: bool,
+}
+
+impl ReduceMotion {
+    // pulls a squash/stretch scale towards 1
+    pub fn soften(&self, scale: f32) -> f32 {
+        if self.enabled {
+            1. + (scale - 1.) * 0.3
+        } else {
+            scale
+        }
     }
 }
 
@@ -26,6 +51,10 @@ impl TransformRotation {
     }
 }
 
+// keeps animating while the gameplay is paused, e.g. the hawk-eye verdict
+#[derive(Component)]
+pub struct PauseExempt;
+
 #[repr(u64)]
 pub enum TweenDoneAction {
     None = 0,
@@ -55,7 +84,48 @@ fn on_tween_completed(mut commands: Commands, mut ev_reader: EventReader<TweenCo
     }
 }
 
-fn rotate(mut q: Query<(&TransformRotation, &mut Transform)>, time: ScaledTime) {
+// any state pushed over or replacing the game
+fn is_paused(state: &GameState) -> bool {
+    !matches!(state, GameState::Game | GameState::Menu)
+}
+
+// same as bevy_tweening's component_animator_system, but on scaled time so tweens respect slow-mo and pausing
+fn scaled_animator_system<T: Component>(
+    mut query: Query<(Entity, &mut T, &mut Animator<T>, Option<&PauseExempt>)>,
+    mut event_writer: EventWriter<TweenCompleted>,
+    state: Res<State<GameState>>,
+    time: GameTime,
+) {
+    let delta = time.scaled_delta();
+
+    if delta.is_zero() {
+        return;
+    }
+
+    let paused = is_paused(state.current());
+
+    for (entity, ref mut target, ref mut animator, exempt) in query.iter_mut() {
+        if paused && exempt.is_none() {
+            continue;
+        }
+
+        if animator.state != AnimatorState::Paused {
+            if let Some(tweenable) = animator.tweenable_mut() {
+                tweenable.tick(delta, target, entity, &mut event_writer);
+            }
+        }
+    }
+}
+
+fn rotate(
+    mut q: Query<(&TransformRotation, &mut Transform)>,
+    time: GameTime,
+    reduce_motion: Res<ReduceMotion>,
+) {
+    if reduce_motion.enabled {
+        return;
+    }
+
     for (r, mut t) in q.iter_mut() {
         t.rotate(Quat::from_rotation_z(
             r.rotation_rad * time.scaled_delta_seconds(),
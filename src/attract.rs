@@ -0,0 +1,193 @@
+use crate::{
+    input_binding::{InputAction, InputAxis, PlayerInput},
+    match_config::GameMode,
+    menu::MENU_INPUT_LABEL,
+    palette::PaletteColor,
+    reset::Persistent,
+    score::GameOverEvt,
+    GameState,
+};
+use bevy::prelude::*;
+
+// AI vs AI demo match once the menu sits idle, any input goes back to the menu
+pub struct AttractPlugin;
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<AttractSettings>()
+            .init_resource::<MenuIdle>()
+            .add_system_set(SystemSet::on_enter(GameState::Menu).with_system(stop_demo))
+            .add_system_set(
+                SystemSet::on_update(GameState::Menu)
+                    // before the menu uses up the swing
+                    .with_system(start_demo.before(MENU_INPUT_LABEL)),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Game).with_system(setup_ui))
+            .add_system_set(SystemSet::on_update(GameState::Game).with_system(leave_demo));
+    }
+}
+
+pub struct AttractSettings {
+    pub enabled: bool,
+    // how long the menu has to sit untouched
+    pub idle_sec: f32,
+}
+
+impl Default for AttractSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_sec: 20.,
+        }
+    }
+}
+
+#[derive(Default)]
+struct MenuIdle(f32);
+
+// the mode picked in the menu, restored once the demo is over
+struct AttractReturnMode(GameMode);
+
+#[derive(Component)]
+struct AttractText;
+
+fn has_any_input(input: &PlayerInput) -> bool {
+    (1..=4).any(|id| {
+        input.just_pressed(id, InputAction::Swing)
+            || input.just_pressed(id, InputAction::Dash)
+            || input
+                .get_xy_axes_raw(id, &InputAxis::MoveX, &InputAxis::MoveY)
+                .length()
+                >= 0.5
+    })
+}
+
+fn start_demo(
+    mut commands: Commands,
+    input: Res<PlayerInput>,
+    settings: Res<AttractSettings>,
+    mut idle: ResMut<MenuIdle>,
+    mut game_mode: ResMut<GameMode>,
+    mut state: ResMut<State<GameState>>,
+    time: Res<Time>,
+) {
+    if !settings.enabled || has_any_input(&input) {
+        idle.0 = 0.;
+        return;
+    }
+
+    idle.0 += time.delta_seconds();
+
+    if idle.0 >= settings.idle_sec {
+        match state.set(GameState::Game) {
+            Ok(_) => {
+                debug!("Starting the attract demo");
+                commands.insert_resource(AttractReturnMode(*game_mode));
+                *game_mode = GameMode::Attract;
+            }
+            Err(e) => warn!("Failed to start the attract demo: {:?}", e),
+        }
+    }
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, game_mode: Res<GameMode>) {
+    if *game_mode != GameMode::Attract {
+        return;
+    }
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(20.),
+                    bottom: Val::Px(20.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "Demo - press swing",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(AttractText)
+        .insert(Name::new("AttractText"));
+}
+
+// the demo has no results, it just ends
+fn leave_demo(
+    mut input: ResMut<PlayerInput>,
+    mut ev_r_game_over: EventReader<GameOverEvt>,
+    game_mode: Res<GameMode>,
+    mut state: ResMut<State<GameState>>,
+) {
+    if *game_mode != GameMode::Attract {
+        return;
+    }
+
+    let game_over = ev_r_game_over.iter().count() > 0;
+
+    if game_over || has_any_input(&input) {
+        for id in 1..=4 {
+            // so the menu doesn't pick up the same press
+            input.use_button_action(id, InputAction::Swing);
+        }
+
+        if let Err(e) = state.set(GameState::Menu) {
+            warn!("Failed to stop the attract demo: {:?}", e);
+        }
+    }
+}
+
+fn stop_demo(
+    mut commands: Commands,
+    return_mode: Option<Res<AttractReturnMode>>,
+    mut game_mode: ResMut<GameMode>,
+    mut idle: ResMut<MenuIdle>,
+    despawn_q: Query<Entity, (Without<Persistent>, Without<Parent>)>,
+) {
+    idle.0 = 0.;
+
+    if let Some(return_mode) = return_mode {
+        *game_mode = return_mode.0;
+        commands.remove_resource::<AttractReturnMode>();
+
+        // unlike a real match there are no results to clean up the court
+        for e in despawn_q.iter() {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stopping_the_demo_restores_the_menu_mode() {
+        let mut app = App::new();
+        app.init_resource::<MenuIdle>()
+            .insert_resource(GameMode::Attract)
+            .insert_resource(AttractReturnMode(GameMode::Solo))
+            .add_system(stop_demo);
+
+        let court_e = app.world.spawn().insert(Persistent).id();
+        let player_e = app.world.spawn().id();
+        app.update();
+
+        assert_eq!(
+            *app.world.get_resource::<GameMode>().unwrap(),
+            GameMode::Solo
+        );
+        assert!(app.world.get_resource::<AttractReturnMode>().is_none());
+        assert!(app.world.get_entity(court_e).is_some());
+        assert!(app.world.get_entity(player_e).is_none());
+    }
+}
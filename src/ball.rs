@@ -4,31 +4,57 @@ use bevy::{
     math::Vec2,
     prelude::*,
     sprite::{Sprite, SpriteBundle},
+    transform::TransformSystem,
 };
 use bevy_extensions::Vec2Conversion;
 
 use crate::{
+    ai_player_controller::AiAimTarget,
     animation::TweenDoneAction,
+    combo::Combo,
+    ends::Ends,
     extra::TransformBundle,
+    game_speed::GameTime,
+    hawk_eye::{is_close_call, HawkEyeReviewEvt, HawkEyeSettings},
     level::{CourtRegion, CourtSettings, InitialRegion, NetOffset, ServingRegion},
+    match_config::{MatchConfig, ServeNetRule},
     palette::{Palette, PaletteColor},
+    perk::Perks,
     physics::PhysLayer,
-    player::{Player, PlayerAim, PlayerSwing},
+    player::{
+        is_left_player_id, JumpStats, Player, PlayerAim, PlayerSwing, SwingConfig, AIM_CLAMP_Y,
+    },
     player_action::PlayerActionStatus,
+    practice::ServePractice,
     render::{BALL_Z, PLAYER_Z, SHADOW_Z},
+    rng::GameRng,
     trail::{FadeOutTrail, Trail},
+    warm_up::WarmUpDoneEvt,
     GameSetupPhase, GameState,
 };
 use bevy_inspector_egui::Inspectable;
 use bevy_prototype_lyon::prelude::*;
-use bevy_time::{ScaledTime, ScaledTimeDelta};
 use bevy_tweening::lens::{SpriteColorLens, TransformScaleLens};
 use bevy_tweening::*;
 use heron::*;
 use rand::*;
 
+// nice2have: scale with the court size once that's configurable
 pub const BALL_MAX_SPEED: f32 = 1100.;
+const BALL_SERVE_MAX_SPEED: f32 = 1250.;
 const BALL_SIZE: f32 = 35.;
+const BALL_TRAIL_WIDTH: f32 = 30.;
+// the shadow is at its smallest & faintest from this bounce height up
+const BALL_SHADOW_FADE_HEIGHT: f32 = 60.;
+// same step as the prediction, so fast balls at low fps don't skip the net/land checks
+const BALL_STEP_SEC: f32 = 1. / 60.;
+const POWER_SHOT_SPEED_MULT: f32 = 1.35;
+// serves charged past this start to spray
+const SERVE_RISK_CHARGE: f32 = 0.85;
+// balls crossing the net below this bounce height get blocked
+const NET_HEIGHT: f32 = 20.;
+const BALL_COLLISIONS_LABEL: &str = "ball_collisions";
+const BALL_MOVEMENT_LABEL: &str = "ball_movement";
 
 pub struct BallPlugin;
 impl Plugin for BallPlugin {
@@ -36,18 +62,137 @@ impl Plugin for BallPlugin {
         app.add_system_set(
             SystemSet::on_enter(GameState::Game).with_system(setup.label(GameSetupPhase::Ball)),
         )
-        .add_system_to_stage(CoreStage::PostUpdate, handle_collisions)
+        .add_system_set(SystemSet::on_exit(GameState::Game).with_system(despawn_balls))
+        .add_system_to_stage(CoreStage::PreUpdate, restore_ball_sim_pos)
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            handle_collisions.label(BALL_COLLISIONS_LABEL),
+        )
         .add_system_to_stage(CoreStage::PostUpdate, handle_regions)
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            interpolate_ball
+                .after(BALL_COLLISIONS_LABEL)
+                .before(TransformSystem::TransformPropagate),
+        )
+        // after all the status changes & despawns
+        .add_system_to_stage(CoreStage::Last, update_current_ball)
         .add_system_set(
             SystemSet::on_update(GameState::Game)
-                .with_system(movement)
-                .with_system(bounce),
+                .with_system(movement.label(BALL_MOVEMENT_LABEL))
+                .with_system(bounce.after(BALL_MOVEMENT_LABEL))
+                .with_system(update_shadow)
+                .with_system(spawn_first_serve)
+                .with_system(spawn_pending_serve),
         )
         .add_event::<BallBouncedEvt>()
-        .add_event::<BallHitEvt>();
+        .add_event::<BallHitEvt>()
+        .add_event::<FaultEvt>()
+        .add_event::<AceEvt>()
+        .init_resource::<ShotSettings>()
+        .init_resource::<DeadBallSettings>()
+        .init_resource::<BallInterpolation>()
+        .init_resource::<CurrentBall>();
+    }
+}
+
+pub struct ShotSettings {
+    // share of the incoming ball speed added to the return, off by default
+    pub carry_over: f32,
+    // hitting the ball before it bounces
+    pub volley_carry_over: f32,
+    // share of the incoming speed lost before it's carried over, so fast rallies don't stay pinned at max speed
+    pub carry_over_damping: f32,
+    // serves use their own top speed, the swing charge still scales it
+    pub serve_max_speed: f32,
+    // a fully charged serve goes past the rally cap
+    pub serve_charge_cap: f32,
+    // max random y deflection of a fully charged serve - reckless serves fault more
+    pub serve_fault_scaling: f32,
+    // shortest rally shot (to the 1st bounce) as a share of court.right, so it scales w/ the court
+    // serves are left alone, a weak serve should still be able to fault
+    pub min_distance_ratio: f32,
+    pub heat: BallHeatSettings,
+}
+
+impl Default for ShotSettings {
+    fn default() -> Self {
+        Self {
+            carry_over: 0.,
+            volley_carry_over: 0.,
+            carry_over_damping: 0.25,
+            serve_max_speed: BALL_SERVE_MAX_SPEED,
+            serve_charge_cap: 1.1,
+            serve_fault_scaling: 0.35,
+            min_distance_ratio: 0.35,
+            heat: BallHeatSettings::default(),
+        }
+    }
+}
+
+// long rallies heat the ball up past the usual speed cap, every point starts with a new (cold) ball
+pub struct BallHeatSettings {
+    pub enabled: bool,
+    // hits (serve incl.) before the ball starts heating up
+    pub start_hit: usize,
+    // hits from the start to full heat
+    pub hits_to_max: usize,
+    // share of the max speed added at full heat
+    pub max_speed_bonus: f32,
+}
+
+impl Default for BallHeatSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            start_hit: 6,
+            hits_to_max: 12,
+            max_speed_bonus: 0.2,
+        }
     }
 }
 
+impl BallHeatSettings {
+    // 0..1
+    pub fn get_heat(&self, hit_count: usize) -> f32 {
+        if !self.enabled {
+            return 0.;
+        }
+
+        (hit_count.saturating_sub(self.start_hit) as f32 / self.hits_to_max.max(1) as f32).min(1.)
+    }
+}
+
+// renders the ball between the last 2 fixed steps instead of sub-stepping the frame delta
+// smoother at uneven fps, but the rendered ball lags up to a step behind
+#[derive(Default)]
+pub struct BallInterpolation {
+    pub enabled: bool,
+}
+
+pub struct DeadBallSettings {
+    // 'point over' beat before the next serve spawns
+    pub delay_sec: f32,
+    // the ball stops & pulses where a point was won before it scales out, 0 to scale out right away
+    // pushes the next serve back by the same time
+    pub point_freeze_sec: f32,
+}
+
+impl Default for DeadBallSettings {
+    fn default() -> Self {
+        Self {
+            delay_sec: 0.6,
+            point_freeze_sec: 0.,
+        }
+    }
+}
+
+// inserted by on_ball_bounced once a point/serve ends
+pub struct PendingServe {
+    pub timer: Timer,
+    pub fault_count: u8,
+}
+
 #[derive(Default, Component, Inspectable)]
 pub struct Ball {
     pub dir: Vec2,
@@ -56,7 +201,22 @@ pub struct Ball {
     pub region: CourtRegion,
     pub bounce_e: Option<Entity>,
     pub trail_e: Option<Entity>,
+    pub shadow_e: Option<Entity>,
+    // incl. the serve
+    pub hit_count: usize,
+    // blocked by the net - the point goes against the hitter
+    pub netted: bool,
+    // clipped the net but made it over
+    pub net_cord: bool,
+    // can't hit the ball again before it crosses the net
+    pub last_hitter: Option<usize>,
+    // 0..1, see BallHeatSettings
+    pub heat: f32,
     prev_pos: Vec3,
+    // fixed step position (see BallInterpolation)
+    sim_pos: Vec3,
+    sim_prev_pos: Vec3,
+    step_acc: f32,
     size: f32,
 }
 
@@ -66,85 +226,453 @@ pub struct BallBounce {
     gravity: f32,
     velocity: f32,
     max_velocity: f32,
+    // (pos, count) of the bounces stepped since the last bounce run
+    #[inspectable(ignore)]
+    landings: Vec<(Vec2, usize)>,
 }
 
-#[derive(Default, Component, Inspectable)]
+#[derive(Default, Component, Inspectable, Clone, Copy, PartialEq, Debug)]
 pub enum BallStatus {
     Serve(CourtRegion, u8, usize),
     Fault(u8, usize),
+    // serve clipped the net and landed in - replayed w/o adding a fault
+    Let(u8, usize),
     Rally(usize),
     #[default]
     Used,
 }
 
+// the ball in play, so consumers don't have to look it up themselves
+#[derive(Default)]
+pub struct CurrentBall {
+    pub entity: Option<Entity>,
+    pub status: BallStatus,
+}
+
+impl CurrentBall {
+    pub fn is_live(&self) -> bool {
+        self.entity.is_some() && self.status != BallStatus::Used
+    }
+
+    pub fn is_serving(&self) -> bool {
+        matches!(self.status, BallStatus::Serve(..))
+    }
+}
+
+// where and when the ball is expected to bounce next
+#[derive(Default, Component)]
+pub struct BallPrediction {
+    pub bounce_pos: Vec2,
+    pub flight_sec: f32,
+    pub elapsed_sec: f32,
+    // (pos incl. the bounce height, time)
+    pub arc: Vec<(Vec2, f32)>,
+}
+
+#[derive(Clone, Copy)]
 pub struct BallBouncedEvt {
     pub ball_e: Entity,
     pub bounce_count: usize,
     pub side: f32,
+    pub pos: Vec2,
+}
+
+pub struct FaultEvt {
+    pub ball_e: Entity,
+    pub player_id: usize,
+    pub fault_count: u8,
+    // double fault or whatever the fault limit is
+    pub point_lost: bool,
+    // into the net rather than out/wrong box
+    pub netted: bool,
+    pub pos: Vec2,
+}
+
+// a serve the receiver never got to
+pub struct AceEvt {
+    pub player_id: usize,
 }
 
 pub struct BallHitEvt {
     pub ball_e: Entity,
     pub player_id: usize,
+    pub strength: f32,
+    pub power_shot: bool,
+    // hit before the ball bounced on the hitter's side
+    pub volley: bool,
+}
+
+fn setup(mut commands: Commands, region: Res<InitialRegion>) {
+    commands.insert_resource(ServingRegion(region.0));
+    commands.remove_resource::<PendingServe>();
+}
+
+// the trail isn't a child of the ball, the bounce sprite & the shadow are
+fn despawn_balls(mut commands: Commands, ball_q: Query<(Entity, &Ball)>, entity_q: Query<Entity>) {
+    for (e, ball) in ball_q.iter() {
+        if let Some(trail_e) = ball.trail_e.and_then(|trail_e| entity_q.get(trail_e).ok()) {
+            commands.entity(trail_e).despawn_recursive();
+        }
+
+        commands.entity(e).despawn_recursive();
+    }
+
+    commands.remove_resource::<PendingServe>();
 }
 
-fn setup(
+// the first ball waits for the warm-up
+fn spawn_first_serve(
     mut commands: Commands,
+    mut ev_r_warm_up_done: EventReader<WarmUpDoneEvt>,
     asset_server: Res<AssetServer>,
-    region: Res<InitialRegion>,
+    serving_region: Res<ServingRegion>,
+    ends: Res<Ends>,
     court_set: Res<CourtSettings>,
+    mut rng: ResMut<GameRng>,
 ) {
-    spawn_ball(
-        &mut commands,
-        &asset_server,
-        region.0,
-        0,
-        region.0.get_player_id(),
-        &court_set,
-    );
-    commands.insert_resource(ServingRegion(region.0));
+    for _ev in ev_r_warm_up_done.iter() {
+        spawn_ball(
+            &mut commands,
+            &asset_server,
+            serving_region.0,
+            0,
+            ends.get_player_id(serving_region.0.is_left()),
+            &court_set,
+            &mut rng.0,
+        );
+    }
+}
+
+fn spawn_pending_serve(
+    mut commands: Commands,
+    pending: Option<ResMut<PendingServe>>,
+    asset_server: Res<AssetServer>,
+    serving_region: Res<ServingRegion>,
+    ends: Res<Ends>,
+    court_set: Res<CourtSettings>,
+    mut rng: ResMut<GameRng>,
+    time: GameTime,
+) {
+    if let Some(mut pending) = pending {
+        pending.timer.tick(time.scaled_delta());
+
+        if pending.timer.finished() {
+            commands.remove_resource::<PendingServe>();
+            spawn_ball(
+                &mut commands,
+                &asset_server,
+                serving_region.0,
+                pending.fault_count,
+                ends.get_player_id(serving_region.0.is_left()),
+                &court_set,
+                &mut rng.0,
+            );
+        }
+    }
 }
 
 // nice2have: try - slowly speedup during rally?
 fn movement(
     mut ball_q: Query<(&mut Ball, &mut Transform)>,
-    mut bounce_q: Query<&mut BallBounce>,
-    time: ScaledTime,
+    mut bounce_q: Query<(&mut BallBounce, &mut Transform), Without<Ball>>,
+    time: GameTime,
     net: Res<NetOffset>,
+    interpolation: Res<BallInterpolation>,
 ) {
     for (mut ball, mut ball_t) in ball_q.iter_mut() {
-        if ball.dir == Vec2::ZERO {
-            continue;
+        move_ball(
+            &mut ball,
+            &mut ball_t,
+            time.scaled_delta_seconds(),
+            &mut bounce_q,
+            net.current_offset,
+            interpolation.enabled,
+        );
+    }
+}
+
+fn move_ball(
+    ball: &mut Ball,
+    ball_t: &mut Transform,
+    delta_sec: f32,
+    bounce_q: &mut Query<(&mut BallBounce, &mut Transform), Without<Ball>>,
+    net_x: f32,
+    interpolate: bool,
+) {
+    if interpolate {
+        // fixed steps, the leftover is rendered by interpolate_ball
+        ball.step_acc += delta_sec;
+        while ball.step_acc >= BALL_STEP_SEC {
+            ball.step_acc -= BALL_STEP_SEC;
+            ball.sim_prev_pos = ball_t.translation;
+            step_ball(ball, ball_t, BALL_STEP_SEC, bounce_q, net_x);
+        }
+    } else {
+        let mut remaining = delta_sec;
+
+        while remaining > 0. && ball.dir != Vec2::ZERO {
+            let dt = remaining.min(BALL_STEP_SEC);
+            remaining -= dt;
+            step_ball(ball, ball_t, dt, bounce_q, net_x);
         }
 
-        let speed = ball.dir.length();
+        ball.step_acc = 0.;
+        ball.sim_prev_pos = ball_t.translation;
+    }
+
+    ball.sim_pos = ball_t.translation;
+}
+
+// moves the ball & its height on the same step, so a bounce is caught at the step it happened
+fn step_ball(
+    ball: &mut Ball,
+    ball_t: &mut Transform,
+    dt: f32,
+    bounce_q: &mut Query<(&mut BallBounce, &mut Transform), Without<Ball>>,
+    net_x: f32,
+) {
+    if ball.dir == Vec2::ZERO {
+        return;
+    }
+
+    let speed = ball.dir.length();
+
+    if speed < 0.025 {
+        ball.dir = Vec2::ZERO;
+        return;
+    }
+
+    // very simple drag
+    let drag_mult = if speed < 0.25 { 1. } else { 0.35 };
+    // todo: figure out a stable drag curve fn
+    ball.dir *= 1. - drag_mult * dt;
+
+    // move
+    let vel = ball.dir.to_vec3() * ball.max_speed;
+    ball_t.translation += vel * dt;
+    ball.speed = vel.length();
+
+    let ball_x = ball_t.translation.x;
+    let ball_prev_x = ball.prev_pos.x;
+    let crossed_net =
+        (ball_prev_x < net_x && ball_x > net_x) || (ball_prev_x > net_x && ball_x < net_x);
+    if crossed_net {
+        ball.last_hitter = None;
+    }
+
+    if let Ok((mut bounce, mut bounce_t)) = bounce_q.get_mut(ball.bounce_e.unwrap()) {
+        if crossed_net {
+            bounce.count = 0;
+            info!("crossed net extra check");
+        }
 
+        // height
+        bounce.velocity += bounce.gravity * dt;
+        bounce_t.translation.y += bounce.velocity * dt;
+
+        if bounce_t.translation.y <= 0. {
+            bounce_t.translation.y = 0.01;
+            bounce.velocity = get_bounce_velocity(ball.dir.length(), bounce.max_velocity);
+            bounce.count += 1;
+            let count = bounce.count;
+            bounce.landings.push((ball_t.translation.truncate(), count));
+        }
+    }
+
+    ball.prev_pos = ball_t.translation;
+}
+
+// gameplay runs on the simulated position, only the rendered one is interpolated
+fn restore_ball_sim_pos(
+    mut ball_q: Query<(&Ball, &mut Transform)>,
+    interpolation: Res<BallInterpolation>,
+) {
+    if !interpolation.enabled {
+        return;
+    }
+
+    for (ball, mut ball_t) in ball_q.iter_mut() {
+        ball_t.translation = ball.sim_pos;
+    }
+}
+
+fn interpolate_ball(
+    mut ball_q: Query<(&Ball, &mut Transform)>,
+    interpolation: Res<BallInterpolation>,
+) {
+    if !interpolation.enabled {
+        return;
+    }
+
+    for (ball, mut ball_t) in ball_q.iter_mut() {
+        let t = (ball.step_acc / BALL_STEP_SEC).clamp(0., 1.);
+        ball_t.translation = ball.sim_prev_pos.lerp(ball.sim_pos, t);
+    }
+}
+
+// steps through the same drag and gravity as `step_ball`
+fn predict_ball_flight(
+    mut pos: Vec2,
+    mut dir: Vec2,
+    max_speed: f32,
+    mut height: f32,
+    mut velocity: f32,
+    gravity: f32,
+) -> BallPrediction {
+    let step = BALL_STEP_SEC;
+    let mut elapsed = 0.;
+    let mut arc = vec![(pos + Vec2::Y * height, 0.)];
+
+    while elapsed < 5. {
+        let speed = dir.length();
         if speed < 0.025 {
-            ball.dir = Vec2::ZERO;
-            return;
+            break;
         }
 
-        // very simple drag
         let drag_mult = if speed < 0.25 { 1. } else { 0.35 };
-        // todo: figure out a stable drag curve fn
-        ball.dir *= 1. - drag_mult * time.scaled_delta_seconds();
-
-        // move
-        let vel = ball.dir.to_vec3() * ball.max_speed;
-        ball_t.translation += vel * time.scaled_delta_seconds();
-        ball.speed = vel.length();
-
-        let net_x = net.0;
-        let ball_x = ball_t.translation.x;
-        let ball_prev_x = ball.prev_pos.x;
-        if (ball_prev_x < net_x && ball_x > net_x) || (ball_prev_x > net_x && ball_x < net_x) {
-            if let Ok(mut bounce) = bounce_q.get_mut(ball.bounce_e.unwrap()) {
-                bounce.count = 0;
-                info!("crossed net extra check");
+        dir *= 1. - drag_mult * step;
+        pos += dir * max_speed * step;
+        velocity += gravity * step;
+        height += velocity * step;
+        elapsed += step;
+
+        if height <= 0. {
+            break;
+        }
+
+        arc.push((pos + Vec2::Y * height, elapsed));
+    }
+
+    BallPrediction {
+        bounce_pos: pos,
+        flight_sec: elapsed,
+        elapsed_sec: 0.,
+        arc,
+    }
+}
+
+// (serve, volley) for a hit by the given player
+fn get_hit_kind(status: BallStatus, player_id: usize, bounce_count: usize) -> (bool, bool) {
+    let is_serve = matches!(status, BallStatus::Serve(_, _, id) if id == player_id);
+    // no bounce since the ball crossed the net, a vollied serve included
+    let volley = !is_serve && bounce_count == 0;
+    (is_serve, volley)
+}
+
+// nearest balls first - the first hit puts the swing on cooldown, so a swing only ever hits one ball
+fn sort_nearest_first<T>(hits: &mut [(f32, T)]) {
+    hits.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+}
+
+// the launch velocity only depends on the aim, the speed mult doesn't raise the arc
+fn get_shot_launch_velocity(dir: Vec2, bounce: &BallBounce) -> f32 {
+    get_bounce_velocity(dir.length(), bounce.max_velocity)
+}
+
+// flight of a hit along the aim dir, same as the actual hit
+fn predict_shot(
+    pos: Vec2,
+    dir: Vec2,
+    speed_mult: f32,
+    max_speed: f32,
+    height: f32,
+    bounce: &BallBounce,
+) -> BallPrediction {
+    predict_ball_flight(
+        pos,
+        dir * speed_mult,
+        max_speed,
+        height,
+        get_shot_launch_velocity(dir, bounce),
+        bounce.gravity,
+    )
+}
+
+// weakest speed mult from the given one up that carries the ball min_distance (along x) before it bounces
+fn get_min_distance_speed_mult(
+    min_distance: f32,
+    speed_mult: f32,
+    pos: Vec2,
+    dir: Vec2,
+    max_speed: f32,
+    height: f32,
+    bounce: &BallBounce,
+) -> f32 {
+    let mut mult = speed_mult;
+
+    while mult < 1. {
+        let prediction = predict_shot(pos, dir, mult, max_speed, height, bounce);
+
+        if (prediction.bounce_pos.x - pos.x).abs() >= min_distance {
+            break;
+        }
+
+        mult = (mult + 0.05).min(1.);
+    }
+
+    mult
+}
+
+fn update_current_ball(ball_q: Query<(Entity, &BallStatus)>, mut current: ResMut<CurrentBall>) {
+    let (entity, status) = ball_q
+        .iter()
+        .find(|(_, status)| **status != BallStatus::Used)
+        .map_or((None, BallStatus::Used), |(e, status)| (Some(e), *status));
+
+    // only touch the resource on change so consumers can rely on change detection
+    if current.entity != entity || current.status != status {
+        current.entity = entity;
+        current.status = status;
+    }
+}
+
+// smaller & fainter the higher the ball is
+fn update_shadow(
+    ball_q: Query<&Ball>,
+    bounce_q: Query<&Transform, With<BallBounce>>,
+    mut shadow_q: Query<(&mut Transform, &mut Sprite), Without<BallBounce>>,
+    palette: Res<Palette>,
+) {
+    let col = palette.get_color(&PaletteColor::Shadow);
+
+    for ball in ball_q.iter() {
+        if let (Some(bounce_e), Some(shadow_e)) = (ball.bounce_e, ball.shadow_e) {
+            if let (Ok(bounce_t), Ok((mut shadow_t, mut sprite))) =
+                (bounce_q.get(bounce_e), shadow_q.get_mut(shadow_e))
+            {
+                let height_t = (bounce_t.translation.y / BALL_SHADOW_FADE_HEIGHT).clamp(0., 1.);
+                shadow_t.scale = Vec2::splat(1. - 0.5 * height_t).extend(1.);
+                sprite.color = col;
+                sprite.color.set_a(col.a() * (1. - 0.6 * height_t));
             }
         }
+    }
+}
 
-        ball.prev_pos = ball_t.translation;
+// what a serve turns into on its first bounce
+fn get_serve_status(
+    serve_region: CourtRegion,
+    fault_count: u8,
+    player_id: usize,
+    landing_region: CourtRegion,
+    netted: bool,
+    net_cord: bool,
+    config: &MatchConfig,
+) -> BallStatus {
+    let net_fault = net_cord && config.serve_net_rule == ServeNetRule::Fault;
+    let in_box = if config.strict_serve_box {
+        Some(landing_region) == serve_region.get_inverse()
+    } else {
+        // arcade - anywhere in the receiver's half
+        !landing_region.is_out_of_bounds() && landing_region.is_left() != serve_region.is_left()
+    };
+
+    if netted || net_fault || !in_box {
+        BallStatus::Fault(fault_count.saturating_add(1), player_id)
+    } else if net_cord {
+        BallStatus::Let(fault_count, player_id)
+    } else {
+        BallStatus::Rally(player_id)
     }
 }
 
@@ -153,64 +681,83 @@ fn get_bounce_velocity(dir_len: f32, max_velocity: f32) -> f32 {
 }
 
 fn bounce(
-    mut bounce_query: Query<
-        (&mut BallBounce, &mut Transform, &GlobalTransform, &Parent),
-        Without<Ball>,
-    >,
-    mut ball_q: Query<(Entity, &mut Ball, &mut BallStatus, &Transform)>,
+    mut bounce_query: Query<(&mut BallBounce, &Parent), Without<Ball>>,
+    mut ball_q: Query<(Entity, &Ball, &mut BallStatus)>,
     mut ev_w_bounce: EventWriter<BallBouncedEvt>,
+    mut ev_w_fault: EventWriter<FaultEvt>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     palette: Res<Palette>,
-    time: ScaledTime,
     net: Res<NetOffset>,
+    config: Res<MatchConfig>,
+    court_set: Res<CourtSettings>,
+    (hawk_eye, mut ev_w_review): (Res<HawkEyeSettings>, EventWriter<HawkEyeReviewEvt>),
 ) {
-    for (mut ball_bounce, mut t, _bounce_global_t, p) in bounce_query.iter_mut() {
-        if let Ok((ball_e, ball, mut ball_status, ball_t)) = ball_q.get_mut(p.0) {
-            if ball.dir == Vec2::ZERO {
-                continue;
-            }
-
-            ball_bounce.velocity += ball_bounce.gravity * time.scaled_delta_seconds();
-            t.translation.y += ball_bounce.velocity * time.scaled_delta_seconds();
-
-            if t.translation.y <= 0. {
-                t.translation.y = 0.01;
-                ball_bounce.velocity =
-                    get_bounce_velocity(ball.dir.length(), ball_bounce.max_velocity);
-                ball_bounce.count += 1;
-                trace!("Bounce {}", ball_bounce.count);
+    for (mut ball_bounce, p) in bounce_query.iter_mut() {
+        if let Ok((ball_e, ball, mut ball_status)) = ball_q.get_mut(p.0) {
+            // stepped by movement, evaluated where the ball actually landed
+            for (pos, count) in ball_bounce.landings.drain(..) {
+                trace!("Bounce {}", count);
 
                 // eval serve on bounce
                 if let BallStatus::Serve(region, fault_count, player_id) = *ball_status {
-                    if ball.region != region.get_inverse().unwrap() {
-                        // fault
-                        *ball_status = BallStatus::Fault(fault_count + 1, player_id);
-                        debug!("Bad serve {:?} => {:?}", region, ball.region);
-                    } else {
-                        // good serve
-                        *ball_status = BallStatus::Rally(player_id);
-                        debug!("Good serve {:?} => {:?}", region, ball.region);
+                    // the region colliders snap to the target offset
+                    let landing_region = court_set.get_region(
+                        pos,
+                        config.out_margin,
+                        net.target_offset,
+                        region.get_inverse(),
+                    );
+                    *ball_status = get_serve_status(
+                        region,
+                        fault_count,
+                        player_id,
+                        landing_region,
+                        ball.netted,
+                        ball.net_cord,
+                        &config,
+                    );
+
+                    match *ball_status {
+                        BallStatus::Fault(fault_count, _) => {
+                            ev_w_fault.send(FaultEvt {
+                                ball_e,
+                                player_id,
+                                fault_count,
+                                point_lost: config.fault_limit.is_reached(fault_count),
+                                netted: ball.netted,
+                                pos,
+                            });
+                            debug!("Bad serve {:?} => {:?}", region, landing_region);
+                        }
+                        BallStatus::Let(..) => debug!("Let {:?} => {:?}", region, landing_region),
+                        _ => debug!("Good serve {:?} => {:?}", region, landing_region),
                     }
                 }
 
-                ev_w_bounce.send(BallBouncedEvt {
+                let bounce_evt = BallBouncedEvt {
                     ball_e,
-                    bounce_count: ball_bounce.count,
-                    side: if ball_t.translation.x < net.0 {
-                        -1.
-                    } else {
-                        1.
-                    },
-                });
-
-                spawn_bounce_track(
-                    &mut commands,
-                    &asset_server,
-                    &palette,
-                    ball_t.translation.truncate().extend(SHADOW_Z),
-                );
-                debug!("Bounced {} times", ball_bounce.count);
+                    bounce_count: count,
+                    side: if pos.x < net.current_offset { -1. } else { 1. },
+                    pos,
+                };
+
+                // only the first bounce decides in/out
+                if hawk_eye.enabled
+                    && count == 1
+                    && is_close_call(pos, &court_set, config.out_margin, hawk_eye.margin)
+                {
+                    ev_w_review.send(HawkEyeReviewEvt {
+                        bounce_evt,
+                        is_in: !court_set.is_out_of_bounds(pos, config.out_margin)
+                            && !matches!(*ball_status, BallStatus::Fault(..)),
+                    });
+                } else {
+                    ev_w_bounce.send(bounce_evt);
+                }
+
+                spawn_bounce_track(&mut commands, &asset_server, &palette, pos.extend(SHADOW_Z));
+                debug!("Bounced {} times", count);
             }
         }
     }
@@ -253,49 +800,128 @@ fn spawn_bounce_track(
 
 // nice2have: 'auto dash swing'?
 fn handle_collisions(
+    mut commands: Commands,
     mut coll_er: EventReader<CollisionEvent>,
     mut ball_hit_ew: EventWriter<BallHitEvt>,
-    mut ball_q: Query<(&mut Ball, &mut BallStatus, &Children)>,
-    mut ball_bounce_q: Query<&mut BallBounce>,
+    mut ball_q: Query<(&mut Ball, &mut BallStatus, &Children, &GlobalTransform)>,
+    mut ball_bounce_q: Query<(&mut BallBounce, &Transform)>,
     player_aim_q: Query<&PlayerAim>,
-    mut player_q: Query<(&Player, &mut PlayerSwing, &GlobalTransform)>,
+    mut player_q: Query<(
+        &Player,
+        &mut PlayerSwing,
+        &mut Combo,
+        &Perks,
+        &JumpStats,
+        &GlobalTransform,
+        Option<&AiAimTarget>,
+    )>,
+    mut trail_q: Query<&mut Trail>,
+    shot_settings: Res<ShotSettings>,
+    swing_config: Res<SwingConfig>,
+    mut rng: ResMut<GameRng>,
+    practice: Res<ServePractice>,
+    court_set: Res<CourtSettings>,
 ) {
-    for ev in coll_er.iter() {
+    let get_hit_distance = |ev: &CollisionEvent| {
+        let (entity_1, entity_2) = ev.rigid_body_entities();
+        let ball_pos = ball_q.get(entity_1).or_else(|_| ball_q.get(entity_2));
+        let player_pos = player_q.get(entity_1).or_else(|_| player_q.get(entity_2));
+
+        match (ball_pos, player_pos) {
+            (Ok(b), Ok(p)) => b.3.translation.distance_squared(p.5.translation),
+            _ => f32::MAX,
+        }
+    };
+    let mut events: Vec<(f32, CollisionEvent)> = coll_er
+        .iter()
+        .map(|ev| (get_hit_distance(ev), ev.clone()))
+        .collect();
+    sort_nearest_first(&mut events);
+
+    for (_, ev) in events {
         if ev.is_started() {
             let mut ball;
             let mut status;
             let ball_e;
             let other_e;
             let bounce_e;
+            let ball_pos;
             let (entity_1, entity_2) = ev.rigid_body_entities();
             if let Ok(b) = ball_q.get_mut(entity_1) {
                 ball = b.0;
                 status = b.1;
                 ball_e = entity_1;
                 bounce_e = b.2.iter().next().unwrap();
+                ball_pos = b.3.translation.truncate();
                 other_e = entity_2;
             } else if let Ok(b) = ball_q.get_mut(entity_2) {
                 ball = b.0;
                 status = b.1;
                 ball_e = entity_2;
                 bounce_e = b.2.iter().next().unwrap();
+                ball_pos = b.3.translation.truncate();
                 other_e = entity_1;
             } else {
                 continue;
             }
 
-            let mut ball_bounce = ball_bounce_q.get_mut(*bounce_e).unwrap();
+            let (mut ball_bounce, bounce_t) = ball_bounce_q.get_mut(*bounce_e).unwrap();
 
-            if let Ok((player, mut swing, _player_t)) = player_q.get_mut(other_e) {
+            if let Ok((player, mut swing, mut combo, perks, jump, player_t, ai_target)) =
+                player_q.get_mut(other_e)
+            {
                 if let PlayerActionStatus::Active(ball_speed_multiplier) = swing.status {
-                    if !swing.timer.finished() {
+                    let in_reach = player_aim_q.get(player.aim_e).map_or(true, |aim| {
+                        swing_config.hitbox.is_in_reach(
+                            ball_pos - player_t.translation.truncate(),
+                            aim.dir,
+                            swing.radius,
+                        )
+                    }) && bounce_t.translation.y
+                        <= jump.get_reach(ball_speed_multiplier);
+
+                    if !swing.timer.finished() && ball.last_hitter != Some(player.id) && in_reach {
                         swing.start_cooldown();
+                        ball.hit_count += 1;
+                        ball.last_hitter = Some(player.id);
+
+                        let power_shot = combo.is_full();
+                        if power_shot {
+                            combo.reset();
+                        }
+
+                        let (is_serve, volley) =
+                            get_hit_kind(*status, player.id, ball_bounce.count);
+                        ball.heat = shot_settings.heat.get_heat(ball.hit_count);
+                        let max_speed = if is_serve {
+                            shot_settings.serve_max_speed
+                        } else {
+                            BALL_MAX_SPEED * (1. + ball.heat * shot_settings.heat.max_speed_bonus)
+                        };
+                        ball.max_speed = if power_shot {
+                            max_speed * POWER_SHOT_SPEED_MULT
+                        } else {
+                            max_speed
+                        };
+
+                        if let Ok(mut trail) = trail_q.get_mut(ball.trail_e.unwrap()) {
+                            trail.max_width = if power_shot {
+                                BALL_TRAIL_WIDTH * 1.75
+                            } else {
+                                BALL_TRAIL_WIDTH
+                            };
+                        }
 
                         if let Ok(aim) = player_aim_q.get(player.aim_e) {
                             let mut dir = aim.dir;
 
+                            if let Some(target) = ai_target.and_then(|t| t.0) {
+                                let to_target = target - ball_pos;
+                                dir = to_target / to_target.x.abs().max(1.);
+                            }
+
                             let clamp_x = 1.;
-                            let clamp_y = 0.8;
+                            let clamp_y = perks.get_aim_clamp_y(AIM_CLAMP_Y);
 
                             let player_sign = player.get_sign();
                             if dir == Vec2::new(player_sign, 0.) {
@@ -313,9 +939,66 @@ fn handle_collisions(
                                 );
                             }
 
-                            ball.dir = dir * ball_speed_multiplier;
-                            ball_bounce.velocity =
-                                get_bounce_velocity(dir.length(), ball_bounce.max_velocity);
+                            let carry_over = if volley {
+                                shot_settings.volley_carry_over
+                            } else {
+                                shot_settings.carry_over
+                            };
+                            let incoming_speed = ball.dir.length()
+                                * (1. - shot_settings.carry_over_damping.clamp(0., 1.));
+                            let mut speed_mult =
+                                (ball_speed_multiplier + incoming_speed * carry_over).min(1.);
+
+                            let serve_to_center =
+                                is_serve && practice.enabled && practice.serve_to_center;
+
+                            if serve_to_center {
+                                if let BallStatus::Serve(region, ..) = *status {
+                                    let box_center = Vec2::new(
+                                        -player_sign * court_set.right / 2.,
+                                        if region.is_top() {
+                                            court_set.bottom / 2.
+                                        } else {
+                                            court_set.top / 2.
+                                        },
+                                    );
+                                    let to_center = box_center - ball_pos;
+                                    dir = to_center / to_center.x.abs().max(1.);
+                                    dir.y = dir.y.clamp(-clamp_y, clamp_y);
+                                }
+                            }
+
+                            if is_serve {
+                                if !serve_to_center {
+                                    let risk = ((ball_speed_multiplier - SERVE_RISK_CHARGE)
+                                        / (1. - SERVE_RISK_CHARGE))
+                                        .max(0.);
+                                    let spray: f32 = rng.0.gen_range(-1. ..=1.);
+                                    dir.y += spray * risk * shot_settings.serve_fault_scaling;
+                                }
+                                speed_mult = ball_speed_multiplier * shot_settings.serve_charge_cap;
+                            } else {
+                                speed_mult = get_min_distance_speed_mult(
+                                    court_set.right * shot_settings.min_distance_ratio,
+                                    speed_mult,
+                                    ball_pos,
+                                    dir,
+                                    ball.max_speed,
+                                    bounce_t.translation.y,
+                                    &ball_bounce,
+                                );
+                            }
+
+                            ball.dir = dir * speed_mult;
+                            ball_bounce.velocity = get_shot_launch_velocity(dir, &ball_bounce);
+                            commands.entity(ball_e).insert(predict_shot(
+                                ball_pos,
+                                dir,
+                                speed_mult,
+                                ball.max_speed,
+                                bounce_t.translation.y,
+                                &ball_bounce,
+                            ));
 
                             let rot = Quat::from_rotation_arc_2d(Vec2::Y, dir)
                                 .to_euler(EulerRot::XYZ)
@@ -324,7 +1007,10 @@ fn handle_collisions(
                             trace!("Hit rot {:?}", rot);
 
                             match *status {
-                                BallStatus::Serve(_, _, player_id) if player_id != player.id => {
+                                BallStatus::Serve(_, _, player_id)
+                                    if is_left_player_id(player_id)
+                                        != is_left_player_id(player.id) =>
+                                {
                                     // vollied serve
                                     *status = BallStatus::Rally(player.id);
                                     trace!("Vollied serve");
@@ -340,6 +1026,9 @@ fn handle_collisions(
                         ball_hit_ew.send(BallHitEvt {
                             ball_e,
                             player_id: player.id,
+                            strength: ball_speed_multiplier,
+                            power_shot,
+                            volley,
                         });
                     }
                 }
@@ -355,8 +1044,11 @@ fn handle_regions(
     mut ball_mut_q: Query<&mut Ball>,
     mut ball_bounce_q: Query<(&mut BallBounce, &Transform)>,
     region_q: Query<&CourtRegion>,
+    status_q: Query<&BallStatus>,
     court_set: Res<CourtSettings>,
+    config: Res<MatchConfig>,
     entity_q: Query<Entity, Without<Ball>>,
+    mut rng: ResMut<GameRng>,
 ) {
     let all_events: Vec<CollisionEvent> = coll_events.iter().cloned().collect();
     for (ball_e, ball_t) in ball_q.iter() {
@@ -407,11 +1099,27 @@ fn handle_regions(
                         ball_bounce_q.get_mut(ball.bounce_e.unwrap())
                     {
                         bounce.count = 0;
+                        ball.last_hitter = None;
                         trace!("Crossed net");
                         trace!("height over net {}", bounce_t.translation.y);
 
-                        if bounce_t.translation.y < 20. {
+                        let net_cord = bounce_t.translation.y < NET_HEIGHT
+                            && config.net_cord_enabled
+                            && matches!(
+                                status_q.get(ball_e),
+                                Ok(BallStatus::Rally(..) | BallStatus::Serve(..))
+                            )
+                            && rng.0.gen_bool(config.net_cord_chance.clamp(0., 1.) as f64);
+
+                        if net_cord {
+                            debug!("net cord");
+                            ball.net_cord = true;
+                            // lucky dribble over the net
+                            ball.dir *= 0.2;
+                            bounce.velocity = bounce.velocity.abs() * 0.3;
+                        } else if bounce_t.translation.y < NET_HEIGHT {
                             debug!("hit net");
+                            ball.netted = true;
                             let hit_vel_mult = 0.25;
                             ball.dir *= Vec2::new(-hit_vel_mult, hit_vel_mult);
                             bounce.velocity *= 0.5;
@@ -439,6 +1147,7 @@ pub fn spawn_ball(
     fault_count: u8,
     player_id: usize,
     court_set: &Res<CourtSettings>,
+    rng: &mut impl Rng,
 ) {
     let bounce_e = commands
         .spawn_bundle(SpriteBundle {
@@ -458,7 +1167,7 @@ pub fn spawn_ball(
         .insert(PaletteColor::Ball)
         .id();
 
-    let shadow = commands
+    let shadow_e = commands
         .spawn_bundle(SpriteBundle {
             texture: asset_server.load("art-ish/ball.png"),
             sprite: Sprite {
@@ -484,20 +1193,20 @@ pub fn spawn_ball(
             points: Vec::new(),
             transform_e: bounce_e,
             duration_sec: 0.3,
-            max_width: 30.,
+            max_width: BALL_TRAIL_WIDTH,
         })
         .insert(Name::new("BallTrail"))
         .id();
 
-    let mut rng = rand::thread_rng();
     let x = rng.gen_range((court_set.right / 2.)..=court_set.right) as f32;
     let x = if serve_region.is_left() { -x } else { x };
     let y = rng.gen_range(120..=280) as f32;
     let y = if serve_region.is_bottom() { -y } else { y };
+    let pos = Vec3::new(x, y, BALL_Z);
     commands
         .spawn_bundle(TransformBundle {
             transform: Transform {
-                translation: Vec3::new(x, y, BALL_Z),
+                translation: pos,
                 scale: Vec3::ZERO,
                 ..Default::default()
             },
@@ -510,6 +1219,9 @@ pub fn spawn_ball(
             region: serve_region,
             bounce_e: Some(bounce_e),
             trail_e: Some(trail_e),
+            shadow_e: Some(shadow_e),
+            sim_pos: pos,
+            sim_prev_pos: pos,
             ..Default::default()
         })
         .insert(BallStatus::Serve(serve_region, fault_count, player_id))
@@ -518,7 +1230,7 @@ pub fn spawn_ball(
         .insert(CollisionLayers::all::<PhysLayer>())
         .insert(Name::new("Ball"))
         .add_child(bounce_e)
-        .add_child(shadow)
+        .add_child(shadow_e)
         .insert(Animator::new(Delay::new(Duration::from_millis(500)).then(
             Tween::new(
                 EaseFunction::BackOut,
@@ -531,3 +1243,578 @@ pub fn spawn_ball(
             ),
         )));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Handedness;
+    use bevy::app::Events;
+
+    fn get_test_bounce() -> BallBounce {
+        BallBounce {
+            gravity: -420.,
+            max_velocity: 200.,
+            ..Default::default()
+        }
+    }
+
+    fn get_shot_distance(speed_mult: f32, pos: Vec2, bounce: &BallBounce) -> f32 {
+        predict_shot(pos, Vec2::X, speed_mult, BALL_MAX_SPEED, 0., bounce)
+            .bounce_pos
+            .x
+            - pos.x
+    }
+
+    #[test]
+    fn net_cord_serve_is_a_let_by_default() {
+        let config = MatchConfig::default();
+        assert_eq!(
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                1,
+                1,
+                CourtRegion::TopRight,
+                false,
+                true,
+                &config
+            ),
+            BallStatus::Let(1, 1)
+        );
+    }
+
+    #[test]
+    fn net_cord_serve_faults_w_the_fault_rule() {
+        let config = MatchConfig {
+            serve_net_rule: ServeNetRule::Fault,
+            ..Default::default()
+        };
+        assert_eq!(
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                1,
+                1,
+                CourtRegion::TopRight,
+                false,
+                true,
+                &config
+            ),
+            BallStatus::Fault(2, 1)
+        );
+        // a clean serve is still good
+        assert_eq!(
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                1,
+                1,
+                CourtRegion::TopRight,
+                false,
+                false,
+                &config
+            ),
+            BallStatus::Rally(1)
+        );
+    }
+
+    #[test]
+    fn no_ball_survives_the_game_exit() {
+        let mut app = App::new();
+        app.add_system(despawn_balls);
+
+        for _ in 0..2 {
+            let trail_e = app.world.spawn().insert(Name::new("BallTrail")).id();
+            let bounce_e = app.world.spawn().id();
+            let mut ball = Ball::default();
+            ball.trail_e = Some(trail_e);
+            ball.bounce_e = Some(bounce_e);
+            app.world
+                .spawn()
+                .insert(ball)
+                .insert(BallStatus::Rally(1))
+                .push_children(&[bounce_e]);
+        }
+        app.world.insert_resource(PendingServe {
+            timer: Timer::from_seconds(1., false),
+            fault_count: 0,
+        });
+
+        app.update();
+
+        assert_eq!(app.world.query::<&Ball>().iter(&app.world).count(), 0);
+        assert_eq!(app.world.query::<&BallStatus>().iter(&app.world).count(), 0);
+        assert_eq!(app.world.query::<&Name>().iter(&app.world).count(), 0);
+        assert!(app.world.get_resource::<PendingServe>().is_none());
+    }
+
+    #[test]
+    fn netted_serve_faults_then_double_faults() {
+        let config = MatchConfig::default();
+        let serve = |fault_count| {
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                fault_count,
+                1,
+                // a netted ball never makes it to the other side
+                CourtRegion::BottomLeft,
+                true,
+                false,
+                &config,
+            )
+        };
+
+        assert_eq!(serve(0), BallStatus::Fault(1, 1));
+        assert!(!config.fault_limit.is_reached(1));
+        assert_eq!(serve(1), BallStatus::Fault(2, 1));
+        assert!(config.fault_limit.is_reached(2));
+    }
+
+    #[test]
+    fn serve_into_the_wrong_box() {
+        let strict = MatchConfig {
+            strict_serve_box: true,
+            ..Default::default()
+        };
+        let arcade = MatchConfig {
+            strict_serve_box: false,
+            ..Default::default()
+        };
+
+        // the diagonal box is good either way
+        for config in [&strict, &arcade] {
+            assert_eq!(
+                get_serve_status(
+                    CourtRegion::BottomLeft,
+                    0,
+                    1,
+                    CourtRegion::TopRight,
+                    false,
+                    false,
+                    config
+                ),
+                BallStatus::Rally(1)
+            );
+        }
+
+        // the receiver's other box only counts in arcade
+        assert_eq!(
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                0,
+                1,
+                CourtRegion::BottomRight,
+                false,
+                false,
+                &strict
+            ),
+            BallStatus::Fault(1, 1)
+        );
+        assert_eq!(
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                0,
+                1,
+                CourtRegion::BottomRight,
+                false,
+                false,
+                &arcade
+            ),
+            BallStatus::Rally(1)
+        );
+
+        // the server's own half & out are faults either way
+        for config in [&strict, &arcade] {
+            for landing_region in [CourtRegion::TopLeft, CourtRegion::OutOfBounds] {
+                assert_eq!(
+                    get_serve_status(
+                        CourtRegion::BottomLeft,
+                        0,
+                        1,
+                        landing_region,
+                        false,
+                        false,
+                        config
+                    ),
+                    BallStatus::Fault(1, 1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn serve_landing_respects_the_out_margin() {
+        let court_set = CourtSettings {
+            left: -550.,
+            right: 550.,
+            top: 300.,
+            bottom: -300.,
+            base_region_size: Vec3::ONE,
+            region_x: 275.,
+        };
+        let target = CourtRegion::BottomLeft.get_inverse();
+        let config = MatchConfig::default();
+        let serve = |pos: Vec2, margin: f32, net_x: f32| {
+            get_serve_status(
+                CourtRegion::BottomLeft,
+                0,
+                1,
+                court_set.get_region(pos, margin, net_x, target),
+                false,
+                false,
+                &config,
+            )
+        };
+
+        // just past the baseline & just over the service line into the other box
+        for pos in [Vec2::new(555., 100.), Vec2::new(300., -5.)] {
+            assert_eq!(serve(pos, 0., 0.), BallStatus::Fault(1, 1));
+            assert_eq!(serve(pos, 10., 0.), BallStatus::Rally(1));
+        }
+
+        // a strict margin shrinks the box
+        assert_eq!(serve(Vec2::new(545., 100.), 0., 0.), BallStatus::Rally(1));
+        assert_eq!(
+            serve(Vec2::new(545., 100.), -10., 0.),
+            BallStatus::Fault(1, 1)
+        );
+
+        // the net isn't a line, a ball on the server's side is never in
+        assert_eq!(
+            serve(Vec2::new(-5., 100.), 10., 0.),
+            BallStatus::Fault(1, 1)
+        );
+    }
+
+    #[test]
+    fn serve_landing_follows_the_net() {
+        let court_set = CourtSettings {
+            left: -550.,
+            right: 550.,
+            top: 300.,
+            bottom: -300.,
+            base_region_size: Vec3::ONE,
+            region_x: 275.,
+        };
+        let target = CourtRegion::BottomLeft.get_inverse();
+        let pos = Vec2::new(30., 100.);
+
+        assert_eq!(
+            court_set.get_region(pos, 0., 0., target),
+            CourtRegion::TopRight
+        );
+        // the net moved towards the receiver past the landing spot
+        assert_eq!(
+            court_set.get_region(pos, 0., 50., target),
+            CourtRegion::TopLeft
+        );
+        assert_eq!(
+            court_set.get_region(pos, 10., 50., target),
+            CourtRegion::TopLeft
+        );
+    }
+
+    #[test]
+    fn volley_vs_groundstroke() {
+        let serve = BallStatus::Serve(CourtRegion::BottomLeft, 0, 1);
+        assert_eq!(get_hit_kind(serve, 1, 0), (true, false));
+        // the receiver going for the serve before it bounces
+        assert_eq!(get_hit_kind(serve, 2, 0), (false, true));
+        assert_eq!(get_hit_kind(BallStatus::Rally(1), 2, 0), (false, true));
+        assert_eq!(get_hit_kind(BallStatus::Rally(1), 2, 1), (false, false));
+    }
+
+    fn spawn_collision_ball(app: &mut App, pos: Vec2, dir: Vec2) -> Entity {
+        let trail_e = app.world.spawn().id();
+        let bounce_e = app
+            .world
+            .spawn()
+            .insert(BallBounce {
+                count: 1,
+                ..get_test_bounce()
+            })
+            .insert(Transform::default())
+            .id();
+
+        app.world
+            .spawn()
+            .insert(Ball {
+                dir,
+                max_speed: BALL_MAX_SPEED,
+                trail_e: Some(trail_e),
+                bounce_e: Some(bounce_e),
+                ..Default::default()
+            })
+            .insert(BallStatus::Rally(2))
+            .insert(GlobalTransform::from_translation(pos.extend(0.)))
+            .push_children(&[bounce_e])
+            .id()
+    }
+
+    fn get_collision_started(ball_e: Entity, player_e: Entity) -> CollisionEvent {
+        let layers = CollisionLayers::all::<PhysLayer>();
+        CollisionEvent::Started(
+            CollisionData::new(ball_e, ball_e, layers, Vec::new()),
+            CollisionData::new(player_e, player_e, layers, Vec::new()),
+        )
+    }
+
+    #[test]
+    fn swing_only_hits_the_nearest_ball() {
+        let mut app = App::new();
+        app.add_event::<CollisionEvent>()
+            .add_event::<BallHitEvt>()
+            .init_resource::<ShotSettings>()
+            .init_resource::<SwingConfig>()
+            .init_resource::<GameRng>()
+            .init_resource::<ServePractice>()
+            .insert_resource(CourtSettings {
+                left: -550.,
+                right: 550.,
+                top: 300.,
+                bottom: -300.,
+                base_region_size: Vec3::ONE,
+                region_x: 275.,
+            })
+            .add_system(handle_collisions);
+
+        let aim_e = app
+            .world
+            .spawn()
+            .insert(PlayerAim {
+                dir: Vec2::X,
+                ..Default::default()
+            })
+            .id();
+        let player_e = app
+            .world
+            .spawn()
+            .insert(Player {
+                id: 1,
+                aim_e,
+                aim_charge_e: aim_e,
+                handedness: Handedness::default(),
+                left_side: true,
+            })
+            .insert(PlayerSwing {
+                status: PlayerActionStatus::Active(1.),
+                radius: 100.,
+                timer: Timer::from_seconds(1., false),
+                ..Default::default()
+            })
+            .insert(Combo::default())
+            .insert(Perks::default())
+            .insert(JumpStats::default())
+            .insert(GlobalTransform::from_translation(Vec3::new(-400., 0., 0.)))
+            .id();
+
+        let incoming_dir = Vec2::new(-0.5, 0.);
+        let far_e = spawn_collision_ball(&mut app, Vec2::new(-340., 0.), incoming_dir);
+        let near_e = spawn_collision_ball(&mut app, Vec2::new(-380., 0.), incoming_dir);
+
+        // the far ball collides first
+        let mut collisions = app
+            .world
+            .get_resource_mut::<Events<CollisionEvent>>()
+            .unwrap();
+        collisions.send(get_collision_started(far_e, player_e));
+        collisions.send(get_collision_started(near_e, player_e));
+
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<BallStatus>(near_e).unwrap(),
+            BallStatus::Rally(1)
+        );
+        assert!(app.world.get::<Ball>(near_e).unwrap().dir.x > 0.);
+        assert_eq!(
+            *app.world.get::<BallStatus>(far_e).unwrap(),
+            BallStatus::Rally(2)
+        );
+        assert_eq!(app.world.get::<Ball>(far_e).unwrap().dir, incoming_dir);
+    }
+
+    #[test]
+    fn weak_shot_is_raised_to_min_distance() {
+        let bounce = get_test_bounce();
+        let pos = Vec2::new(-400., 0.);
+        let min_distance = 550. * 0.35;
+        let mult = get_min_distance_speed_mult(
+            min_distance,
+            0.05,
+            pos,
+            Vec2::X,
+            BALL_MAX_SPEED,
+            0.,
+            &bounce,
+        );
+        assert!(mult > 0.05);
+        assert!(get_shot_distance(mult, pos, &bounce) >= min_distance);
+    }
+
+    #[test]
+    fn strong_shot_is_left_alone() {
+        let bounce = get_test_bounce();
+        let mult = get_min_distance_speed_mult(
+            100.,
+            0.8,
+            Vec2::ZERO,
+            Vec2::X,
+            BALL_MAX_SPEED,
+            0.,
+            &bounce,
+        );
+        assert_eq!(mult, 0.8);
+    }
+
+    #[test]
+    fn min_distance_scales_with_the_court() {
+        let bounce = get_test_bounce();
+        let ratio = ShotSettings::default().min_distance_ratio;
+        let small_court_right = 300.;
+        let pos = Vec2::new(-small_court_right, 0.);
+        let mult = get_min_distance_speed_mult(
+            small_court_right * ratio,
+            0.05,
+            pos,
+            Vec2::X,
+            BALL_MAX_SPEED,
+            0.,
+            &bounce,
+        );
+        let large_mult = get_min_distance_speed_mult(
+            900. * ratio,
+            0.05,
+            Vec2::new(-900., 0.),
+            Vec2::X,
+            BALL_MAX_SPEED,
+            0.,
+            &bounce,
+        );
+        assert!(large_mult > mult);
+        // a short shot from the baseline still lands in on the small court
+        assert!(pos.x + get_shot_distance(mult, pos, &bounce) <= small_court_right);
+    }
+
+    #[test]
+    fn min_distance_holds_for_the_real_hit() {
+        let bounce = get_test_bounce();
+        let shot_settings = ShotSettings::default();
+        let court_right = 300.;
+        let min_distance = court_right * shot_settings.min_distance_ratio;
+        let pos = Vec2::new(-court_right + 20., 0.);
+        let height = 10.;
+
+        // a weak, angled shot (aim dirs are clamped to x = 1, so they're longer than 1)
+        for dir in [Vec2::X, Vec2::new(1., AIM_CLAMP_Y)] {
+            let mult = get_min_distance_speed_mult(
+                min_distance,
+                0.05,
+                pos,
+                dir,
+                BALL_MAX_SPEED,
+                height,
+                &bounce,
+            );
+
+            // same params the hit uses for the ball & its prediction
+            let prediction = predict_shot(pos, dir, mult, BALL_MAX_SPEED, height, &bounce);
+            assert!(prediction.bounce_pos.x - pos.x >= min_distance);
+            assert!(prediction.bounce_pos.x <= court_right);
+        }
+    }
+
+    // a long frame, e.g. a hitch
+    const SLOW_FRAME_SEC: f32 = 0.25;
+
+    fn move_balls_slow_frame(
+        mut ball_q: Query<(&mut Ball, &mut Transform)>,
+        mut bounce_q: Query<(&mut BallBounce, &mut Transform), Without<Ball>>,
+    ) {
+        for (mut ball, mut ball_t) in ball_q.iter_mut() {
+            move_ball(
+                &mut ball,
+                &mut ball_t,
+                SLOW_FRAME_SEC,
+                &mut bounce_q,
+                0.,
+                false,
+            );
+        }
+    }
+
+    #[test]
+    fn fast_ball_bounces_where_it_lands_on_a_slow_frame() {
+        let mut app = App::new();
+        app.add_event::<BallBouncedEvt>()
+            .add_event::<FaultEvt>()
+            .add_event::<HawkEyeReviewEvt>()
+            .insert_resource(AssetServer::new(
+                bevy::asset::FileAssetIo::new("assets"),
+                bevy::tasks::TaskPool::new(),
+            ))
+            .insert_resource(crate::palette::PaletteTheme(0).get_palette())
+            .insert_resource(NetOffset::default())
+            .insert_resource(MatchConfig::default())
+            .insert_resource(CourtSettings {
+                left: -550.,
+                right: 550.,
+                top: 300.,
+                bottom: -300.,
+                base_region_size: Vec3::ONE,
+                region_x: 275.,
+            })
+            .insert_resource(HawkEyeSettings {
+                enabled: false,
+                ..Default::default()
+            })
+            .add_system(move_balls_slow_frame.label(BALL_MOVEMENT_LABEL))
+            .add_system(bounce.after(BALL_MOVEMENT_LABEL));
+
+        let start = Vec2::new(-500., 0.);
+        let height = 30.;
+        let ball_bounce = get_test_bounce();
+        let expected = predict_ball_flight(
+            start,
+            Vec2::X,
+            BALL_MAX_SPEED,
+            height,
+            0.,
+            ball_bounce.gravity,
+        );
+        // lands mid-frame on the 2nd frame
+        assert!(expected.flight_sec > SLOW_FRAME_SEC && expected.flight_sec < SLOW_FRAME_SEC * 2.);
+
+        let bounce_e = app
+            .world
+            .spawn()
+            .insert(ball_bounce)
+            .insert(Transform::from_xyz(0., height, 0.))
+            .id();
+        let ball_e = app
+            .world
+            .spawn()
+            .insert(Ball {
+                dir: Vec2::X,
+                max_speed: BALL_MAX_SPEED,
+                bounce_e: Some(bounce_e),
+                ..Default::default()
+            })
+            .insert(BallStatus::Rally(1))
+            .insert(Transform::from_translation(start.extend(0.)))
+            .push_children(&[bounce_e])
+            .id();
+
+        app.update();
+        app.update();
+
+        let events = app.world.get_resource::<Events<BallBouncedEvt>>().unwrap();
+        let bounces: Vec<_> = events.get_reader().iter(events).copied().collect();
+        assert_eq!(bounces.len(), 1);
+        assert_eq!(bounces[0].bounce_count, 1);
+        assert!(bounces[0].pos.distance(expected.bounce_pos) < 0.1);
+        assert_eq!(bounces[0].side, -1.);
+
+        // the rest of the frame isn't lost
+        let ball_x = app.world.get::<Transform>(ball_e).unwrap().translation.x;
+        assert!(ball_x > bounces[0].pos.x);
+        assert!(app.world.get::<Transform>(bounce_e).unwrap().translation.y > 0.);
+    }
+}
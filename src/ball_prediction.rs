@@ -0,0 +1,316 @@
+use crate::{
+    ball::{BallBouncedEvt, BallHitEvt, BallPrediction},
+    game_speed::GameTime,
+    palette::{Palette, PaletteColor},
+    render::{BALL_Z, SHADOW_Z},
+    GameState,
+};
+use bevy::prelude::*;
+
+const ARC_DOT_SIZE: f32 = 8.;
+// every nth simulated step gets a dot
+const ARC_DOT_SPACING: usize = 4;
+const LANDING_MARKER_SIZE: f32 = 60.;
+const GHOST_BALL_SIZE: f32 = 35.;
+
+pub struct BallPredictionPlugin;
+impl Plugin for BallPredictionPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<PredictionSettings>()
+            .add_system_set(SystemSet::on_exit(GameState::Game).with_system(despawn_markers))
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(tick_predictions)
+                    .with_system(spawn_arc)
+                    .with_system(update_arc)
+                    .with_system(despawn_arc_on_bounce)
+                    .with_system(spawn_landing_marker)
+                    .with_system(update_landing_marker)
+                    .with_system(despawn_landing_marker_on_bounce)
+                    .with_system(spawn_ghost_ball)
+                    .with_system(update_ghost_ball)
+                    .with_system(despawn_ghost_ball_on_bounce),
+            );
+    }
+}
+
+// training aids
+pub struct PredictionSettings {
+    pub show_arc: bool,
+    pub show_landing_marker: bool,
+    // a faint ball running ahead along the predicted arc to practice timing
+    pub show_ghost_ball: bool,
+    pub ghost_lead_sec: f32,
+}
+
+impl Default for PredictionSettings {
+    fn default() -> Self {
+        Self {
+            show_arc: false,
+            show_landing_marker: true,
+            show_ghost_ball: false,
+            ghost_lead_sec: 0.15,
+        }
+    }
+}
+
+#[derive(Component)]
+struct LandingMarker {
+    ball_e: Entity,
+}
+
+#[derive(Component)]
+struct GhostBall {
+    ball_e: Entity,
+}
+
+#[derive(Component)]
+struct ArcDot {
+    ball_e: Entity,
+    time: f32,
+}
+
+fn despawn_markers(
+    mut commands: Commands,
+    marker_q: Query<Entity, Or<(With<LandingMarker>, With<ArcDot>, With<GhostBall>)>>,
+) {
+    for e in marker_q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn tick_predictions(mut prediction_q: Query<&mut BallPrediction>, time: GameTime) {
+    for mut prediction in prediction_q.iter_mut() {
+        prediction.elapsed_sec += time.scaled_delta_seconds();
+    }
+}
+
+fn spawn_arc(
+    mut commands: Commands,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    prediction_q: Query<&BallPrediction>,
+    dot_q: Query<(Entity, &ArcDot)>,
+    asset_server: Res<AssetServer>,
+    settings: Res<PredictionSettings>,
+) {
+    for ev in ev_r_hit.iter() {
+        for (e, dot) in dot_q.iter() {
+            if dot.ball_e == ev.ball_e {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+
+        if !settings.show_arc {
+            continue;
+        }
+
+        if let Ok(prediction) = prediction_q.get(ev.ball_e) {
+            for (pos, time) in prediction.arc.iter().step_by(ARC_DOT_SPACING).skip(1) {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        texture: asset_server.load("art-ish/ball.png"),
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::ONE * ARC_DOT_SIZE),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(pos.extend(BALL_Z - 0.5)),
+                        ..Default::default()
+                    })
+                    .insert(PaletteColor::BallArc)
+                    .insert(ArcDot {
+                        ball_e: ev.ball_e,
+                        time: *time,
+                    })
+                    .insert(Name::new("BallArcDot"));
+            }
+        }
+    }
+}
+
+fn update_arc(
+    mut commands: Commands,
+    mut dot_q: Query<(Entity, &ArcDot, &mut Sprite)>,
+    prediction_q: Query<&BallPrediction>,
+    palette: Res<Palette>,
+) {
+    let col = palette.get_color(&PaletteColor::BallArc);
+
+    for (e, dot, mut sprite) in dot_q.iter_mut() {
+        if let Ok(prediction) = prediction_q.get(dot.ball_e) {
+            // fade out as the ball travels and hide the dots it has already passed
+            let progress = (prediction.elapsed_sec / prediction.flight_sec.max(0.01)).min(1.);
+            let alpha = if dot.time < prediction.elapsed_sec {
+                0.
+            } else {
+                col.a() * (1. - progress)
+            };
+            sprite.color = col;
+            sprite.color.set_a(alpha);
+        } else {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+fn despawn_arc_on_bounce(
+    mut commands: Commands,
+    mut ev_r_bounce: EventReader<BallBouncedEvt>,
+    dot_q: Query<(Entity, &ArcDot)>,
+) {
+    for ev in ev_r_bounce.iter() {
+        for (e, dot) in dot_q.iter() {
+            if dot.ball_e == ev.ball_e {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn spawn_landing_marker(
+    mut commands: Commands,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    prediction_q: Query<&BallPrediction>,
+    marker_q: Query<(Entity, &LandingMarker)>,
+    asset_server: Res<AssetServer>,
+    settings: Res<PredictionSettings>,
+) {
+    for ev in ev_r_hit.iter() {
+        // one marker per ball
+        for (e, marker) in marker_q.iter() {
+            if marker.ball_e == ev.ball_e {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+
+        if !settings.show_landing_marker {
+            continue;
+        }
+
+        if let Ok(prediction) = prediction_q.get(ev.ball_e) {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    texture: asset_server.load("art-ish/ball.png"),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::new(1.0, 0.5) * LANDING_MARKER_SIZE),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(prediction.bounce_pos.extend(SHADOW_Z)),
+                    ..Default::default()
+                })
+                .insert(PaletteColor::Shadow)
+                .insert(LandingMarker { ball_e: ev.ball_e })
+                .insert(Name::new("LandingMarker"));
+        }
+    }
+}
+
+fn update_landing_marker(
+    mut commands: Commands,
+    mut marker_q: Query<(Entity, &LandingMarker, &mut Transform)>,
+    prediction_q: Query<&BallPrediction>,
+) {
+    for (e, marker, mut marker_t) in marker_q.iter_mut() {
+        if let Ok(prediction) = prediction_q.get(marker.ball_e) {
+            // collapse right as the ball lands
+            let remaining = 1. - prediction.elapsed_sec / prediction.flight_sec.max(0.01);
+            marker_t.scale = Vec3::ONE * remaining.clamp(0., 1.);
+        } else {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+fn despawn_landing_marker_on_bounce(
+    mut commands: Commands,
+    mut ev_r_bounce: EventReader<BallBouncedEvt>,
+    marker_q: Query<(Entity, &LandingMarker)>,
+) {
+    for ev in ev_r_bounce.iter() {
+        for (e, marker) in marker_q.iter() {
+            if marker.ball_e == ev.ball_e {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn spawn_ghost_ball(
+    mut commands: Commands,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    prediction_q: Query<&BallPrediction>,
+    ghost_q: Query<(Entity, &GhostBall)>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    settings: Res<PredictionSettings>,
+) {
+    for ev in ev_r_hit.iter() {
+        for (e, ghost) in ghost_q.iter() {
+            if ghost.ball_e == ev.ball_e {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+
+        if !settings.show_ghost_ball {
+            continue;
+        }
+
+        if let Ok(prediction) = prediction_q.get(ev.ball_e) {
+            let pos = prediction
+                .arc
+                .first()
+                .map_or(prediction.bounce_pos, |(p, _)| *p);
+            let mut col = palette.get_color(&PaletteColor::Ball);
+            col.set_a(0.3);
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    texture: asset_server.load("art-ish/ball.png"),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::ONE * GHOST_BALL_SIZE),
+                        color: col,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(pos.extend(BALL_Z - 0.5)),
+                    ..Default::default()
+                })
+                .insert(GhostBall { ball_e: ev.ball_e })
+                .insert(Name::new("GhostBall"));
+        }
+    }
+}
+
+fn update_ghost_ball(
+    mut commands: Commands,
+    mut ghost_q: Query<(Entity, &GhostBall, &mut Transform)>,
+    prediction_q: Query<&BallPrediction>,
+    settings: Res<PredictionSettings>,
+) {
+    for (e, ghost, mut ghost_t) in ghost_q.iter_mut() {
+        if let Ok(prediction) = prediction_q.get(ghost.ball_e) {
+            let time = prediction.elapsed_sec + settings.ghost_lead_sec;
+            // first simulated point past the lead time, waits at the bounce spot otherwise
+            let pos = prediction
+                .arc
+                .iter()
+                .find(|(_, t)| *t >= time)
+                .map_or(prediction.bounce_pos, |(p, _)| *p);
+            ghost_t.translation = pos.extend(ghost_t.translation.z);
+        } else {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+fn despawn_ghost_ball_on_bounce(
+    mut commands: Commands,
+    mut ev_r_bounce: EventReader<BallBouncedEvt>,
+    ghost_q: Query<(Entity, &GhostBall)>,
+) {
+    for ev in ev_r_bounce.iter() {
+        for (e, ghost) in ghost_q.iter() {
+            if ghost.ball_e == ev.ball_e {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+    }
+}
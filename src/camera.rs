@@ -1,19 +1,233 @@
-use bevy::prelude::*;
+use bevy::{
+    input::mouse::{MouseMotion, MouseWheel},
+    prelude::*,
+};
+use rand::{thread_rng, Rng};
 
-use crate::reset::Persistent;
+use crate::{
+    animation::ReduceMotion,
+    ball::{Ball, BallHitEvt},
+    crowd::CrowdIntensity,
+    game_speed::GameTime,
+    hawk_eye::{HawkEyeReview, HawkEyeSettings},
+    input_binding::{InputAction, PlayerInput},
+    player::Player,
+    reset::Persistent,
+};
+
+const FOLLOW_LABEL: &str = "camera_follow";
 
 pub struct CameraPlugin;
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_startup_system(setup);
+        app.init_resource::<CameraConfig>()
+            .add_startup_system(setup)
+            .add_system(toggle_free_camera)
+            .add_system(shake_on_power_shot.before(FOLLOW_LABEL))
+            .add_system(follow_action.label(FOLLOW_LABEL))
+            .add_system(move_free_camera);
+    }
+}
+
+pub struct CameraConfig {
+    // fixed camera when disabled
+    pub dynamic: bool,
+    // orthographic scale - lower is closer
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    pub max_pan: f32,
+    pub follow_strength: f32,
+    // power shots shake the camera, 0 to disable
+    pub shake_strength: f32,
+    pub shake_sec: f32,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self {
+            dynamic: false,
+            min_zoom: 0.85,
+            max_zoom: 1.,
+            max_pan: 80.,
+            follow_strength: 2.5,
+            shake_strength: 6.,
+            shake_sec: 0.25,
+        }
     }
 }
 
+#[derive(Component)]
+pub struct GameCamera {
+    focus: Vec2,
+    zoom: f32,
+    // written by effects like screen shake, composed on top of the follow
+    pub shake_offset: Vec2,
+    shake_sec_left: f32,
+}
+
+// detached camera for recording/debugging, the game keeps running
+// right mouse drag pans, the wheel or page up/down zooms
+pub struct FreeCamera {
+    pos: Vec2,
+    zoom: f32,
+}
+
 fn setup(mut commands: Commands) {
     commands
         .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(GameCamera {
+            focus: Vec2::ZERO,
+            zoom: 1.,
+            shake_offset: Vec2::ZERO,
+            shake_sec_left: 0.,
+        })
         .insert(Persistent);
     commands
         .spawn_bundle(UiCameraBundle::default())
         .insert(Persistent);
 }
+
+fn follow_action(
+    mut cam_q: Query<(&mut GameCamera, &mut Transform)>,
+    ball_q: Query<(&Ball, &GlobalTransform)>,
+    player_q: Query<&GlobalTransform, With<Player>>,
+    config: Res<CameraConfig>,
+    crowd: Res<CrowdIntensity>,
+    review: Option<Res<HawkEyeReview>>,
+    hawk_eye: Res<HawkEyeSettings>,
+    reduce_motion: Res<ReduceMotion>,
+    free_cam: Option<Res<FreeCamera>>,
+    time: GameTime,
+) {
+    if free_cam.is_some() {
+        return;
+    }
+
+    let mut target_focus = Vec2::ZERO;
+    let mut target_zoom = 1.;
+
+    if config.dynamic {
+        if let Some((_, ball_t)) = ball_q.iter().find(|(b, _)| b.dir != Vec2::ZERO) {
+            let ball_pos = ball_t.translation.truncate();
+            let nearest_player_pos =
+                player_q
+                    .iter()
+                    .map(|t| t.translation.truncate())
+                    .min_by(|a, b| {
+                        a.distance_squared(ball_pos)
+                            .partial_cmp(&b.distance_squared(ball_pos))
+                            .unwrap()
+                    });
+
+            target_focus = nearest_player_pos.map_or(ball_pos, |p| (p + ball_pos) / 2.);
+            target_focus = target_focus.clamp_length_max(config.max_pan);
+        }
+
+        // zoom in as the rally heats up
+        let intensity = (crowd.level as f32 / 3.).min(1.);
+        target_zoom = config.max_zoom + (config.min_zoom - config.max_zoom) * intensity;
+    }
+
+    // close call reviews zoom in on the bounce regardless of the camera mode
+    if let Some(review) = review {
+        target_focus = review.bounce_evt.pos;
+        target_zoom = hawk_eye.zoom;
+    }
+
+    let t = (config.follow_strength * time.scaled_delta_seconds()).min(1.);
+
+    for (mut cam, mut cam_t) in cam_q.iter_mut() {
+        cam.focus = cam.focus.lerp(target_focus, t);
+        cam.zoom += (target_zoom - cam.zoom) * t;
+
+        let shake_offset = if reduce_motion.enabled {
+            Vec2::ZERO
+        } else {
+            cam.shake_offset
+        };
+        let pos = cam.focus + shake_offset;
+        cam_t.translation = pos.extend(cam_t.translation.z);
+        cam_t.scale = Vec2::splat(cam.zoom).extend(1.);
+    }
+}
+
+fn shake_on_power_shot(
+    mut cam_q: Query<&mut GameCamera>,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    config: Res<CameraConfig>,
+    time: GameTime,
+) {
+    let power_shot = ev_r_hit.iter().any(|ev| ev.power_shot);
+    // cosmetic, so it stays off GameRng
+    let mut rng = thread_rng();
+
+    for mut cam in cam_q.iter_mut() {
+        if power_shot {
+            cam.shake_sec_left = config.shake_sec;
+        }
+
+        cam.shake_sec_left = (cam.shake_sec_left - time.scaled_delta_seconds()).max(0.);
+        // fades out over the shake
+        let strength = config.shake_strength * cam.shake_sec_left / config.shake_sec.max(0.001);
+        let angle = rng.gen_range(0. ..std::f32::consts::TAU);
+        cam.shake_offset = Vec2::new(angle.cos(), angle.sin()) * strength;
+    }
+}
+
+// the follow picks up from its own focus/zoom once the free camera is gone
+fn toggle_free_camera(
+    mut commands: Commands,
+    input: Res<PlayerInput>,
+    cam_q: Query<&Transform, With<GameCamera>>,
+    free_cam: Option<Res<FreeCamera>>,
+) {
+    if !(1..=4).any(|id| input.just_pressed(id, InputAction::ToggleFreeCamera)) {
+        return;
+    }
+
+    if free_cam.is_some() {
+        commands.remove_resource::<FreeCamera>();
+    } else if let Some(cam_t) = cam_q.iter().next() {
+        commands.insert_resource(FreeCamera {
+            pos: cam_t.translation.truncate(),
+            zoom: cam_t.scale.x,
+        });
+    }
+}
+
+fn move_free_camera(
+    mut cam_q: Query<&mut Transform, With<GameCamera>>,
+    free_cam: Option<ResMut<FreeCamera>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    mut ev_r_motion: EventReader<MouseMotion>,
+    mut ev_r_wheel: EventReader<MouseWheel>,
+    time: Res<Time>,
+) {
+    let mut free_cam = match free_cam {
+        Some(free_cam) => free_cam,
+        None => return,
+    };
+
+    let drag: Vec2 = ev_r_motion.iter().map(|ev| ev.delta).sum();
+    if mouse_buttons.pressed(MouseButton::Right) {
+        let zoom = free_cam.zoom;
+        // screen y is down
+        free_cam.pos += Vec2::new(-drag.x, drag.y) * zoom;
+    }
+
+    let mut zoom_by = ev_r_wheel.iter().map(|ev| -ev.y * 0.1).sum::<f32>();
+    if keys.pressed(KeyCode::PageUp) {
+        zoom_by -= time.delta_seconds();
+    }
+    if keys.pressed(KeyCode::PageDown) {
+        zoom_by += time.delta_seconds();
+    }
+    free_cam.zoom = (free_cam.zoom + zoom_by).clamp(0.2, 3.);
+
+    // no screen shake while detached
+    for mut cam_t in cam_q.iter_mut() {
+        cam_t.translation = free_cam.pos.extend(cam_t.translation.z);
+        cam_t.scale = Vec2::splat(free_cam.zoom).extend(1.);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    match_config::{MatchConfig, ScoringMode},
+    player::{is_left_player_id, Player},
+    score::Score,
+    GameState,
+};
+use bevy::prelude::*;
+
+pub struct ClutchPlugin;
+impl Plugin for ClutchPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<ClutchSettings>()
+            .add_system_set(SystemSet::on_update(GameState::Game).with_system(update_clutch_buffs));
+    }
+}
+
+// rubber-banding for casual play - keep it off for competitive matches
+pub struct ClutchSettings {
+    pub enabled: bool,
+    // games behind in tennis scoring
+    pub games_margin: u8,
+    // points behind in quick play
+    pub points_margin: u8,
+    pub speed_mult: f32,
+}
+
+impl Default for ClutchSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            games_margin: 2,
+            points_margin: 4,
+            speed_mult: 1.08,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ClutchBuff {
+    pub speed_mult: f32,
+}
+
+fn update_clutch_buffs(
+    mut commands: Commands,
+    player_q: Query<(Entity, &Player, Option<&ClutchBuff>)>,
+    added_q: Query<(), Added<Player>>,
+    score: Res<Score>,
+    config: Res<MatchConfig>,
+    settings: Res<ClutchSettings>,
+) {
+    if !score.is_changed() && !settings.is_changed() && added_q.is_empty() {
+        return;
+    }
+
+    let (left, right, margin) = match config.scoring_mode {
+        ScoringMode::Tennis => (
+            score.left_player.games,
+            score.right_player.games,
+            settings.games_margin,
+        ),
+        ScoringMode::FirstTo(_) => (
+            score.left_player.points,
+            score.right_player.points,
+            settings.points_margin,
+        ),
+    };
+
+    for (e, player, buff) in player_q.iter() {
+        let (own, other) = if is_left_player_id(player.id) {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        let behind = settings.enabled && margin > 0 && other.saturating_sub(own) >= margin;
+
+        if behind && buff.is_none() {
+            commands.entity(e).insert(ClutchBuff {
+                speed_mult: settings.speed_mult,
+            });
+        } else if !behind && buff.is_some() {
+            commands.entity(e).remove::<ClutchBuff>();
+        }
+    }
+}
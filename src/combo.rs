@@ -0,0 +1,114 @@
+use crate::{
+    ball::BallHitEvt,
+    palette::PaletteColor,
+    player::{is_left_player_id, Player},
+    reset::Persistent,
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+
+pub struct ComboPlugin;
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_startup_system(setup)
+            .add_system(on_ball_hit)
+            .add_system(update_combo_ui);
+    }
+}
+
+#[derive(Component, Inspectable)]
+pub struct Combo {
+    pub hits: u8,
+    pub hits_for_power_shot: u8,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self {
+            hits: 0,
+            hits_for_power_shot: 5,
+        }
+    }
+}
+
+impl Combo {
+    pub fn is_full(&self) -> bool {
+        self.hits >= self.hits_for_power_shot
+    }
+
+    pub fn reset(&mut self) {
+        self.hits = 0;
+    }
+}
+
+#[derive(Component)]
+struct ComboText {
+    is_left: bool,
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    for is_left in [true, false] {
+        let (left, right) = if is_left {
+            (Val::Px(30.), Val::Auto)
+        } else {
+            (Val::Auto, Val::Px(30.))
+        };
+
+        commands
+            .spawn_bundle(TextBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left,
+                        right,
+                        bottom: Val::Px(20.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                        font_size: 40.0,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            })
+            .insert(PaletteColor::Text)
+            .insert(ComboText { is_left })
+            .insert(Name::new("ComboText"))
+            .insert(Persistent);
+    }
+}
+
+fn on_ball_hit(mut ev_r_hit: EventReader<BallHitEvt>, mut combo_q: Query<(&Player, &mut Combo)>) {
+    for ev in ev_r_hit.iter() {
+        if ev.power_shot {
+            continue;
+        }
+
+        if let Some((_, mut combo)) = combo_q.iter_mut().find(|(p, _)| p.id == ev.player_id) {
+            combo.hits = (combo.hits + 1).min(combo.hits_for_power_shot);
+        }
+    }
+}
+
+fn update_combo_ui(
+    combo_q: Query<(&Player, &Combo), Changed<Combo>>,
+    mut text_q: Query<(&ComboText, &mut Text)>,
+) {
+    for (player, combo) in combo_q.iter() {
+        for (combo_text, mut text) in text_q.iter_mut() {
+            if combo_text.is_left == is_left_player_id(player.id) {
+                text.sections[0].value = if combo.is_full() {
+                    "POWER SHOT".to_string()
+                } else {
+                    format!("combo {}/{}", combo.hits, combo.hits_for_power_shot)
+                };
+            }
+        }
+    }
+}
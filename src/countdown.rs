@@ -0,0 +1,228 @@
+use crate::{
+    animation::TweenDoneAction,
+    ball::{Ball, BallStatus},
+    game_speed::GameTime,
+    input_binding::{InputAction, PlayerInput},
+    palette::{Palette, PaletteColor},
+    player::SWING_LABEL,
+    render::BALL_Z,
+    results::QuickRematch,
+    score::GameWonEvt,
+    warm_up::WarmUpDoneEvt,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::{TextColorLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use std::time::Duration;
+
+pub struct CountdownPlugin;
+impl Plugin for CountdownPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<CountdownSettings>()
+            .init_resource::<GameStart>()
+            .add_system_set(SystemSet::on_enter(GameState::Game).with_system(reset_countdown))
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(queue_countdown)
+                    .with_system(start_countdown)
+                    .with_system(tick_countdown)
+                    .with_system(skip_countdown.before(SWING_LABEL)),
+            );
+    }
+}
+
+pub struct CountdownSettings {
+    pub enabled: bool,
+    pub from: u8,
+    pub step_sec: f32,
+    // count down before every serve, not just the first one of each game
+    pub between_points: bool,
+}
+
+impl Default for CountdownSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            from: 3,
+            step_sec: 0.6,
+            between_points: false,
+        }
+    }
+}
+
+// swing input is ignored while this exists
+pub struct Countdown {
+    remaining: u8,
+    timer: Timer,
+    // only the server can skip
+    server_id: usize,
+}
+
+// the next serve starts a new game
+#[derive(Default)]
+struct GameStart(bool);
+
+#[derive(Component)]
+struct CountdownText;
+
+fn reset_countdown(mut commands: Commands, mut game_start: ResMut<GameStart>) {
+    commands.remove_resource::<Countdown>();
+    game_start.0 = false;
+}
+
+fn queue_countdown(
+    mut ev_r_warm_up_done: EventReader<WarmUpDoneEvt>,
+    mut ev_r_game_won: EventReader<GameWonEvt>,
+    mut game_start: ResMut<GameStart>,
+    quick_rematch: Res<QuickRematch>,
+) {
+    let warm_up_done = ev_r_warm_up_done.iter().count() > 0 && !quick_rematch.0;
+
+    if warm_up_done || ev_r_game_won.iter().count() > 0 {
+        game_start.0 = true;
+    }
+}
+
+fn start_countdown(
+    mut commands: Commands,
+    ball_q: Query<&BallStatus, Added<Ball>>,
+    mut game_start: ResMut<GameStart>,
+    settings: Res<CountdownSettings>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+) {
+    let server_id = match ball_q.iter().find_map(|status| match status {
+        BallStatus::Serve(_, _, player_id) => Some(*player_id),
+        _ => None,
+    }) {
+        Some(server_id) => server_id,
+        None => return,
+    };
+
+    let is_game_start = game_start.0;
+    game_start.0 = false;
+
+    if !settings.enabled || settings.from == 0 || !(is_game_start || settings.between_points) {
+        return;
+    }
+
+    commands.insert_resource(Countdown {
+        remaining: settings.from,
+        timer: Timer::from_seconds(settings.step_sec, true),
+        server_id,
+    });
+    spawn_countdown_text(
+        &mut commands,
+        &asset_server,
+        &palette,
+        settings.from,
+        &settings,
+    );
+}
+
+fn tick_countdown(
+    mut commands: Commands,
+    countdown: Option<ResMut<Countdown>>,
+    settings: Res<CountdownSettings>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    time: GameTime,
+) {
+    if let Some(mut countdown) = countdown {
+        if countdown.timer.tick(time.scaled_delta()).just_finished() {
+            countdown.remaining -= 1;
+
+            if countdown.remaining == 0 {
+                commands.remove_resource::<Countdown>();
+            } else {
+                spawn_countdown_text(
+                    &mut commands,
+                    &asset_server,
+                    &palette,
+                    countdown.remaining,
+                    &settings,
+                );
+            }
+        }
+    }
+}
+
+fn skip_countdown(
+    mut commands: Commands,
+    countdown: Option<Res<Countdown>>,
+    mut input: ResMut<PlayerInput>,
+    text_q: Query<Entity, With<CountdownText>>,
+) {
+    let server_id = match countdown {
+        Some(countdown) => countdown.server_id,
+        None => return,
+    };
+
+    if input.just_pressed(server_id, InputAction::Swing) {
+        // so the skip doesn't start charging the serve
+        input.use_button_action(server_id, InputAction::Swing);
+        commands.remove_resource::<Countdown>();
+
+        for e in text_q.iter() {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+fn spawn_countdown_text(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    palette: &Res<Palette>,
+    value: u8,
+    settings: &CountdownSettings,
+) {
+    let color = palette.get_color(&PaletteColor::Text);
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                value.to_string(),
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 140.,
+                    color,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    vertical: VerticalAlign::Center,
+                },
+            ),
+            transform: Transform {
+                translation: Vec3::Z * (BALL_Z + 1.),
+                scale: Vec2::splat(0.5).extend(1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Animator::new(Tween::new(
+            EaseFunction::BackOut,
+            TweeningType::Once,
+            Duration::from_millis(250),
+            TransformScaleLens {
+                start: Vec2::splat(0.5).extend(1.),
+                end: Vec3::ONE,
+            },
+        )))
+        .insert(Animator::new(
+            Tween::new(
+                EaseFunction::ExponentialIn,
+                TweeningType::Once,
+                Duration::from_secs_f32(settings.step_sec),
+                TextColorLens {
+                    start: color,
+                    end: Color::NONE,
+                    section: 0,
+                },
+            )
+            .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+        ))
+        .insert(CountdownText)
+        .insert(Name::new("CountdownText"));
+}
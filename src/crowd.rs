@@ -0,0 +1,239 @@
+use crate::{
+    animation::TweenDoneAction,
+    ball::{AceEvt, BallHitEvt, FaultEvt},
+    level::CourtSettings,
+    palette::{Palette, PaletteColor},
+    player::is_left_player_id,
+    render::BALL_Z,
+    GameState,
+};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_tweening::{
+    lens::{TextColorLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use std::time::Duration;
+
+pub struct CrowdPlugin;
+impl Plugin for CrowdPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<CrowdSettings>()
+            .init_resource::<CrowdIntensity>()
+            .init_resource::<AceStreaks>()
+            .add_event::<CrowdReactionEvt>()
+            .add_event::<AceStreakEvt>()
+            .add_system_set(SystemSet::on_enter(GameState::Game).with_system(reset_ace_streaks))
+            .add_system(on_ball_hit)
+            .add_system(on_ace)
+            .add_system(break_ace_streaks)
+            .add_system_set(SystemSet::on_update(GameState::Game).with_system(show_crowd_reaction));
+    }
+}
+
+pub struct CrowdSettings {
+    // rally hits needed for each reaction level
+    pub thresholds: Vec<usize>,
+}
+
+impl Default for CrowdSettings {
+    fn default() -> Self {
+        Self {
+            thresholds: vec![4, 8, 14],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CrowdIntensity {
+    pub rally_hits: usize,
+    // 0 is idle ambience
+    pub level: usize,
+}
+
+impl CrowdIntensity {
+    pub fn reset(&mut self) {
+        self.rally_hits = 0;
+        self.level = 0;
+    }
+}
+
+// nice2have: consume in an audio system once there are crowd sounds
+pub struct CrowdReactionEvt {
+    pub level: usize,
+}
+
+// consecutive aces per server
+#[derive(Default)]
+pub struct AceStreaks {
+    pub streaks: HashMap<usize, u32>,
+}
+
+// nice2have: announcer lines once there's audio
+pub struct AceStreakEvt {
+    pub player_id: usize,
+    pub streak: u32,
+}
+
+fn get_reaction_label(level: usize, ace_streak: u32) -> Option<String> {
+    if ace_streak > 1 {
+        return Some(format!("{} ACES IN A ROW", ace_streak));
+    }
+
+    match level {
+        0 => None,
+        1 => Some("Ooh!".to_string()),
+        2 => Some("Wow!".to_string()),
+        _ => Some("WOOO!".to_string()),
+    }
+}
+
+fn reset_ace_streaks(mut streaks: ResMut<AceStreaks>) {
+    streaks.streaks.clear();
+}
+
+fn on_ball_hit(
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    mut ev_w_reaction: EventWriter<CrowdReactionEvt>,
+    mut crowd: ResMut<CrowdIntensity>,
+    settings: Res<CrowdSettings>,
+) {
+    for _ev in ev_r_hit.iter() {
+        crowd.rally_hits += 1;
+
+        let level = settings
+            .thresholds
+            .iter()
+            .filter(|t| crowd.rally_hits >= **t)
+            .count();
+
+        if level > crowd.level {
+            crowd.level = level;
+            trace!("Crowd reaction level {}", level);
+            ev_w_reaction.send(CrowdReactionEvt { level });
+        }
+    }
+}
+
+fn on_ace(
+    mut ev_r_ace: EventReader<AceEvt>,
+    mut ev_w_reaction: EventWriter<CrowdReactionEvt>,
+    mut ev_w_streak: EventWriter<AceStreakEvt>,
+    mut streaks: ResMut<AceStreaks>,
+    settings: Res<CrowdSettings>,
+) {
+    for ev in ev_r_ace.iter() {
+        let streak = streaks.streaks.entry(ev.player_id).or_insert(0);
+        *streak += 1;
+        ev_w_streak.send(AceStreakEvt {
+            player_id: ev.player_id,
+            streak: *streak,
+        });
+
+        // aces get the loudest reaction regardless of the rally length & escalate with the streak
+        ev_w_reaction.send(CrowdReactionEvt {
+            level: settings.thresholds.len().max(1) + *streak as usize - 1,
+        });
+    }
+}
+
+// a returned serve or a lost point ends the server's streak
+fn break_ace_streaks(
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    mut ev_r_fault: EventReader<FaultEvt>,
+    mut streaks: ResMut<AceStreaks>,
+) {
+    for ev in ev_r_hit.iter() {
+        let opponent_sides: Vec<usize> = streaks
+            .streaks
+            .keys()
+            .copied()
+            .filter(|id| is_left_player_id(*id) != is_left_player_id(ev.player_id))
+            .collect();
+
+        for id in opponent_sides {
+            streaks.streaks.remove(&id);
+        }
+    }
+
+    for ev in ev_r_fault.iter().filter(|ev| ev.point_lost) {
+        streaks.streaks.remove(&ev.player_id);
+    }
+}
+
+// nice2have: crowd sprites instead of the text
+fn show_crowd_reaction(
+    mut commands: Commands,
+    mut ev_r_reaction: EventReader<CrowdReactionEvt>,
+    mut ev_r_streak: EventReader<AceStreakEvt>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    court_set: Res<CourtSettings>,
+) {
+    let level = ev_r_reaction.iter().map(|ev| ev.level).max().unwrap_or(0);
+    let ace_streak = ev_r_streak.iter().map(|ev| ev.streak).max().unwrap_or(0);
+
+    if let Some(label) = get_reaction_label(level, ace_streak) {
+        let font_size = 30. + 10. * level.min(4) as f32;
+        let color = palette.get_color(&PaletteColor::Ball);
+
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    label,
+                    TextStyle {
+                        font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                        font_size,
+                        color,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        vertical: VerticalAlign::Center,
+                    },
+                ),
+                transform: Transform {
+                    translation: Vec3::new(0., court_set.top * 0.75, BALL_Z + 1.),
+                    scale: Vec2::splat(0.5).extend(1.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Animator::new(Tween::new(
+                EaseFunction::BackOut,
+                TweeningType::Once,
+                Duration::from_millis(250),
+                TransformScaleLens {
+                    start: Vec2::splat(0.5).extend(1.),
+                    end: Vec3::ONE,
+                },
+            )))
+            .insert(Animator::new(
+                Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(1000),
+                    TextColorLens {
+                        start: color,
+                        end: Color::NONE,
+                        section: 0,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+            ))
+            .insert(Name::new("CrowdReaction"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reaction_label_escalates() {
+        assert_eq!(get_reaction_label(0, 0), None);
+        assert_eq!(get_reaction_label(1, 0).unwrap(), "Ooh!");
+        assert_eq!(get_reaction_label(9, 0).unwrap(), "WOOO!");
+        // the first ace is just a loud reaction
+        assert_eq!(get_reaction_label(3, 1).unwrap(), "WOOO!");
+        assert_eq!(get_reaction_label(4, 2).unwrap(), "2 ACES IN A ROW");
+    }
+}
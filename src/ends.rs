@@ -0,0 +1,182 @@
+use crate::{
+    level::{CourtRegion, ServingRegion},
+    match_config::MatchConfig,
+    player::{is_left_player_id, Player, PlayerAim},
+    score::{GameWonEvt, Score},
+    GameState,
+};
+use bevy::prelude::*;
+
+pub struct EndsPlugin;
+impl Plugin for EndsPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Ends>()
+            // not tied to the game state - the perk selection might get pushed on the same frame
+            .add_system(change_ends)
+            .add_system_set(SystemSet::on_exit(GameState::Game).with_system(reset_ends));
+    }
+}
+
+// the ids stay with the players (and their input), the ends swap under them
+// odd ids start on the left
+#[derive(Default)]
+pub struct Ends {
+    pub swapped: bool,
+}
+
+impl Ends {
+    pub fn is_left(&self, player_id: usize) -> bool {
+        is_left_player_id(player_id) != self.swapped
+    }
+
+    // the (lowest) id playing on the given side
+    pub fn get_player_id(&self, is_left: bool) -> usize {
+        if is_left != self.swapped {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+fn should_change_ends(games_played: u8, config: &MatchConfig) -> bool {
+    config.change_ends_games > 0 && games_played % config.change_ends_games == 0
+}
+
+fn change_ends(
+    mut ev_r_game_won: EventReader<GameWonEvt>,
+    mut ends: ResMut<Ends>,
+    serving_region: Option<ResMut<ServingRegion>>,
+    mut player_q: Query<(&mut Player, &mut Transform)>,
+    mut aim_q: Query<(&mut PlayerAim, &mut Transform), Without<Player>>,
+    score: Res<Score>,
+    config: Res<MatchConfig>,
+) {
+    // the score is already up to date when the game is won
+    let games_played = score.left_player.games + score.right_player.games;
+    if ev_r_game_won.iter().count() == 0 || !should_change_ends(games_played, &config) {
+        return;
+    }
+
+    ends.swapped = !ends.swapped;
+    debug!("Changing ends after {} games", games_played);
+
+    // the same server keeps serving from the mirrored region
+    if let Some(mut serving_region) = serving_region {
+        serving_region.0 =
+            CourtRegion::from_sides(!serving_region.0.is_left(), serving_region.0.is_top());
+    }
+
+    for (mut player, mut player_t) in player_q.iter_mut() {
+        player.left_side = ends.is_left(player.id);
+        player_t.translation.x = -player_t.translation.x;
+
+        // face the net again, the face & the swing ring sync off the changed player
+        if let Ok((mut aim, mut aim_t)) = aim_q.get_mut(player.aim_e) {
+            aim.dir.x = -aim.dir.x;
+            aim_t.rotation = Quat::from_axis_angle(-Vec3::Z, aim.dir.angle_between(Vec2::Y));
+        }
+    }
+}
+
+fn reset_ends(mut ends: ResMut<Ends>) {
+    ends.swapped = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Handedness;
+    use bevy::app::Events;
+
+    #[test]
+    fn ends_change_every_n_games() {
+        let config = MatchConfig {
+            change_ends_games: 2,
+            ..Default::default()
+        };
+        assert!(!should_change_ends(1, &config));
+        assert!(should_change_ends(2, &config));
+        assert!(should_change_ends(4, &config));
+
+        let off = MatchConfig {
+            change_ends_games: 0,
+            ..Default::default()
+        };
+        assert!(!should_change_ends(2, &off));
+    }
+
+    #[test]
+    fn swapped_ends_flip_the_sides_but_keep_the_teams() {
+        let ends = Ends { swapped: true };
+        assert!(!ends.is_left(1));
+        assert!(ends.is_left(2));
+        assert!(!ends.is_left(3));
+        assert_eq!(ends.get_player_id(true), 2);
+        assert_eq!(ends.get_player_id(false), 1);
+        assert_eq!(Ends::default().get_player_id(true), 1);
+    }
+
+    #[test]
+    fn game_won_swaps_the_player_to_the_other_end() {
+        let mut app = App::new();
+        app.add_event::<GameWonEvt>()
+            .init_resource::<Ends>()
+            .insert_resource(ServingRegion(CourtRegion::BottomLeft))
+            .insert_resource(Score::default())
+            .insert_resource(MatchConfig {
+                change_ends_games: 1,
+                ..Default::default()
+            })
+            .add_system(change_ends);
+
+        let aim_e = app
+            .world
+            .spawn()
+            .insert(PlayerAim {
+                dir: Vec2::X,
+                ..Default::default()
+            })
+            .insert(Transform::default())
+            .id();
+        let player_e = app
+            .world
+            .spawn()
+            .insert(Player {
+                id: 1,
+                aim_e,
+                aim_charge_e: aim_e,
+                handedness: Handedness::Right,
+                left_side: true,
+            })
+            .insert(Transform::from_xyz(-200., 50., 0.))
+            .id();
+
+        app.world
+            .get_resource_mut::<Score>()
+            .unwrap()
+            .left_player
+            .games = 1;
+        app.world
+            .get_resource_mut::<Events<GameWonEvt>>()
+            .unwrap()
+            .send(GameWonEvt {
+                winner_id: 1,
+                loser_id: 2,
+            });
+        app.update();
+
+        let player = app.world.get::<Player>(player_e).unwrap();
+        assert_eq!(player.id, 1);
+        assert!(!player.is_left());
+        assert_eq!(
+            app.world.get::<Transform>(player_e).unwrap().translation.x,
+            200.
+        );
+        assert_eq!(app.world.get::<PlayerAim>(aim_e).unwrap().dir, -Vec2::X);
+        assert_eq!(
+            app.world.get_resource::<ServingRegion>().unwrap().0,
+            CourtRegion::BottomRight
+        );
+    }
+}
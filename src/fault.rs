@@ -0,0 +1,109 @@
+use crate::{
+    animation::TweenDoneAction,
+    ball::FaultEvt,
+    palette::{Palette, PaletteColor},
+    player::Player,
+    player_animation::{AgentAnimationData, PlayerAnimation},
+    render::BALL_Z,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::{TextColorLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use std::time::Duration;
+
+pub struct FaultPlugin;
+impl Plugin for FaultPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Game)
+                .with_system(show_fault_marker)
+                .with_system(play_fault_animation),
+        );
+    }
+}
+
+fn show_fault_marker(
+    mut commands: Commands,
+    mut ev_r_fault: EventReader<FaultEvt>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+) {
+    for ev in ev_r_fault.iter() {
+        // a warning for the first fault, a bigger and longer marker once the point is lost
+        let (label, font_size, duration_ms, color) = if ev.point_lost {
+            (
+                if ev.fault_count == 2 {
+                    "DOUBLE FAULT"
+                } else {
+                    "FAULT"
+                },
+                60.,
+                1400,
+                palette.get_color(&PaletteColor::Ball),
+            )
+        } else if ev.netted {
+            ("NET", 40., 900, palette.get_color(&PaletteColor::Text))
+        } else {
+            ("FAULT", 40., 900, palette.get_color(&PaletteColor::Text))
+        };
+
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    label,
+                    TextStyle {
+                        font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                        font_size,
+                        color,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        vertical: VerticalAlign::Center,
+                    },
+                ),
+                transform: Transform {
+                    translation: (ev.pos + Vec2::Y * 50.).extend(BALL_Z + 1.),
+                    scale: Vec2::splat(0.5).extend(1.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Animator::new(Tween::new(
+                EaseFunction::BackOut,
+                TweeningType::Once,
+                Duration::from_millis(250),
+                TransformScaleLens {
+                    start: Vec2::splat(0.5).extend(1.),
+                    end: Vec3::ONE,
+                },
+            )))
+            .insert(Animator::new(
+                Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(duration_ms),
+                    TextColorLens {
+                        start: color,
+                        end: Color::NONE,
+                        section: 0,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+            ))
+            .insert(Name::new("FaultMarker"));
+    }
+}
+
+fn play_fault_animation(
+    mut ev_r_fault: EventReader<FaultEvt>,
+    mut player_q: Query<(&Player, &mut AgentAnimationData)>,
+) {
+    for ev in ev_r_fault.iter() {
+        if let Some((_, mut anim)) = player_q.iter_mut().find(|(p, _)| p.id == ev.player_id) {
+            anim.animation = PlayerAnimation::Disappointed;
+        }
+    }
+}
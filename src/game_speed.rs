@@ -0,0 +1,122 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_time::{ScaledTime, ScaledTimeDelta};
+use std::time::Duration;
+
+pub const GAME_SPEED_MIN: f32 = 0.5;
+pub const GAME_SPEED_MAX: f32 = 1.5;
+// menu step
+const GAME_SPEED_STEP: f32 = 0.25;
+
+pub struct GameSpeedPlugin;
+impl Plugin for GameSpeedPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<GameSpeed>()
+            .init_resource::<TimeScaleMods>();
+    }
+}
+
+// user picked speed - the base scale of everything running on GameTime
+pub struct GameSpeed(f32);
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+impl GameSpeed {
+    pub fn get(&self) -> f32 {
+        self.0
+    }
+
+    pub fn set(&mut self, speed: f32) {
+        self.0 = speed.clamp(GAME_SPEED_MIN, GAME_SPEED_MAX);
+    }
+
+    // wraps around to the min
+    pub fn next(&self) -> f32 {
+        let next = self.0 + GAME_SPEED_STEP;
+        if next > GAME_SPEED_MAX + f32::EPSILON {
+            GAME_SPEED_MIN
+        } else {
+            next
+        }
+    }
+}
+
+// hit-stop, slow-mo & co. keyed by whoever set them
+// they multiply onto the game speed, so they never overwrite the user's pick (or each other)
+#[derive(Default)]
+pub struct TimeScaleMods(HashMap<&'static str, f32>);
+
+impl TimeScaleMods {
+    pub fn set(&mut self, key: &'static str, mult: f32) {
+        self.0.insert(key, mult.max(0.));
+    }
+
+    pub fn remove(&mut self, key: &'static str) {
+        self.0.remove(key);
+    }
+
+    pub fn get_mult(&self) -> f32 {
+        self.0.values().product()
+    }
+}
+
+fn get_time_scale(speed: &GameSpeed, mods: &TimeScaleMods) -> f32 {
+    speed.get() * mods.get_mult()
+}
+
+// ScaledTime w/ the game speed & the mods on top
+#[derive(SystemParam)]
+pub struct GameTime<'w, 's> {
+    time: ScaledTime<'w, 's>,
+    speed: Res<'w, GameSpeed>,
+    mods: Res<'w, TimeScaleMods>,
+}
+
+impl<'w, 's> GameTime<'w, 's> {
+    pub fn scaled_delta(&self) -> Duration {
+        self.time
+            .scaled_delta()
+            .mul_f32(get_time_scale(&self.speed, &self.mods))
+    }
+
+    pub fn scaled_delta_seconds(&self) -> f32 {
+        self.time.scaled_delta_seconds() * get_time_scale(&self.speed, &self.mods)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_speed_is_clamped() {
+        let mut speed = GameSpeed::default();
+        speed.set(3.);
+        assert_eq!(speed.get(), GAME_SPEED_MAX);
+        speed.set(0.1);
+        assert_eq!(speed.get(), GAME_SPEED_MIN);
+    }
+
+    #[test]
+    fn game_speed_wraps_around() {
+        let mut speed = GameSpeed::default();
+        speed.set(GAME_SPEED_MAX);
+        assert_eq!(speed.next(), GAME_SPEED_MIN);
+    }
+
+    #[test]
+    fn mods_multiply_onto_game_speed() {
+        let mut speed = GameSpeed::default();
+        speed.set(1.5);
+        let mut mods = TimeScaleMods::default();
+        mods.set("slow_mo", 0.5);
+        mods.set("hit_stop", 0.);
+        assert_eq!(get_time_scale(&speed, &mods), 0.);
+
+        mods.remove("hit_stop");
+        assert_eq!(get_time_scale(&speed, &mods), 0.75);
+    }
+}
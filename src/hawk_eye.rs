@@ -0,0 +1,298 @@
+use crate::{
+    animation::{PauseExempt, TweenDoneAction},
+    ball::BallBouncedEvt,
+    game_speed::{GameTime, TimeScaleMods},
+    level::CourtSettings,
+    palette::{Palette, PaletteColor},
+    render::BALL_Z,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::{TextColorLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use std::time::Duration;
+
+pub struct HawkEyePlugin;
+impl Plugin for HawkEyePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<HawkEyeSettings>()
+            .add_event::<HawkEyeReviewEvt>()
+            .add_system_set(SystemSet::on_update(GameState::Game).with_system(start_review))
+            .add_system_set(SystemSet::on_enter(GameState::HawkEye).with_system(show_verdict))
+            .add_system_set(SystemSet::on_update(GameState::HawkEye).with_system(finish_review))
+            .add_system_set(SystemSet::on_exit(GameState::HawkEye).with_system(resolve_bounce));
+    }
+}
+
+pub struct HawkEyeSettings {
+    pub enabled: bool,
+    // distance from the court lines that counts as a close call
+    pub margin: f32,
+    pub zoom: f32,
+    // in slow-mo time
+    pub duration_sec: f32,
+    // time scale of the review
+    pub slow_mo: f32,
+}
+
+impl Default for HawkEyeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            margin: 12.,
+            zoom: 0.6,
+            duration_sec: 1.5,
+            slow_mo: 0.5,
+        }
+    }
+}
+
+const SLOW_MO_KEY: &str = "hawk_eye";
+
+// a close call on the first bounce, sent instead of the BallBouncedEvt
+pub struct HawkEyeReviewEvt {
+    pub bounce_evt: BallBouncedEvt,
+    pub is_in: bool,
+}
+
+// gameplay is frozen while this exists, the bounce gets resolved once the review is done
+// nice2have: replay the last shot during the review
+pub struct HawkEyeReview {
+    pub bounce_evt: BallBouncedEvt,
+    pub is_in: bool,
+    pub timer: Timer,
+}
+
+// close to the lines the call is made on - the court lines shifted out by the out margin
+pub fn is_close_call(pos: Vec2, court_set: &CourtSettings, out_margin: f32, margin: f32) -> bool {
+    let min = Vec2::new(court_set.left, court_set.bottom) - Vec2::splat(out_margin);
+    let max = Vec2::new(court_set.right, court_set.top) + Vec2::splat(out_margin);
+    let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+    let dist = (0..corners.len())
+        .map(|i| get_segment_distance(pos, corners[i], corners[(i + 1) % corners.len()]))
+        .fold(f32::MAX, f32::min);
+
+    dist <= margin
+}
+
+fn get_segment_distance(pos: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = ((pos - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0., 1.);
+    pos.distance(a + ab * t)
+}
+
+fn start_review(
+    mut commands: Commands,
+    mut ev_r_review: EventReader<HawkEyeReviewEvt>,
+    mut ev_w_bounce: EventWriter<BallBouncedEvt>,
+    mut state: ResMut<State<GameState>>,
+    hawk_eye: Res<HawkEyeSettings>,
+) {
+    for ev in ev_r_review.iter() {
+        match state.push(GameState::HawkEye) {
+            Ok(_) => commands.insert_resource(HawkEyeReview {
+                bounce_evt: ev.bounce_evt,
+                is_in: ev.is_in,
+                timer: Timer::from_seconds(hawk_eye.duration_sec, false),
+            }),
+            Err(e) => {
+                // e.g. a point was already won this frame - just resolve the bounce w/o the review
+                warn!("Skipping the hawk-eye review: {:?}", e);
+                ev_w_bounce.send(ev.bounce_evt);
+            }
+        }
+    }
+}
+
+fn show_verdict(
+    mut commands: Commands,
+    review: Option<Res<HawkEyeReview>>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    hawk_eye: Res<HawkEyeSettings>,
+    mut time_scale: ResMut<TimeScaleMods>,
+) {
+    if let Some(review) = review {
+        time_scale.set(SLOW_MO_KEY, hawk_eye.slow_mo);
+
+        let (label, color) = if review.is_in {
+            ("IN", palette.get_color(&PaletteColor::Text))
+        } else {
+            ("OUT", palette.get_color(&PaletteColor::Ball))
+        };
+
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    label,
+                    TextStyle {
+                        font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                        font_size: 40.,
+                        color,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        vertical: VerticalAlign::Center,
+                    },
+                ),
+                transform: Transform {
+                    translation: (review.bounce_evt.pos + Vec2::Y * 40.).extend(BALL_Z + 1.),
+                    scale: Vec2::splat(0.5).extend(1.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Animator::new(Tween::new(
+                EaseFunction::BackOut,
+                TweeningType::Once,
+                Duration::from_millis(300),
+                TransformScaleLens {
+                    start: Vec2::splat(0.5).extend(1.),
+                    end: Vec3::ONE,
+                },
+            )))
+            .insert(Animator::new(
+                Tween::new(
+                    EaseFunction::ExponentialIn,
+                    TweeningType::Once,
+                    review.timer.duration(),
+                    TextColorLens {
+                        start: color,
+                        end: Color::NONE,
+                        section: 0,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+            ))
+            .insert(PauseExempt)
+            .insert(Name::new("HawkEyeVerdict"));
+    }
+}
+
+fn finish_review(
+    review: Option<ResMut<HawkEyeReview>>,
+    mut state: ResMut<State<GameState>>,
+    time: GameTime,
+) {
+    let done = match review {
+        Some(mut review) => review.timer.tick(time.scaled_delta()).just_finished(),
+        None => true,
+    };
+
+    if done {
+        if let Err(e) = state.pop() {
+            warn!("Failed to end the hawk-eye review: {:?}", e);
+        }
+    }
+}
+
+fn resolve_bounce(
+    mut commands: Commands,
+    review: Option<Res<HawkEyeReview>>,
+    mut ev_w_bounce: EventWriter<BallBouncedEvt>,
+    mut time_scale: ResMut<TimeScaleMods>,
+) {
+    time_scale.remove(SLOW_MO_KEY);
+
+    if let Some(review) = review {
+        ev_w_bounce.send(review.bounce_evt);
+        commands.remove_resource::<HawkEyeReview>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::Events;
+
+    fn get_review_test_app() -> App {
+        let mut app = App::new();
+        app.add_event::<HawkEyeReviewEvt>()
+            .add_event::<BallBouncedEvt>()
+            .insert_resource(HawkEyeSettings::default())
+            .insert_resource(State::new(GameState::Game))
+            .add_system(start_review);
+
+        app
+    }
+
+    fn send_review(app: &mut App) {
+        app.world
+            .get_resource_mut::<Events<HawkEyeReviewEvt>>()
+            .unwrap()
+            .send(HawkEyeReviewEvt {
+                bounce_evt: BallBouncedEvt {
+                    ball_e: Entity::from_raw(0),
+                    bounce_count: 1,
+                    side: 1.,
+                    pos: Vec2::ZERO,
+                },
+                is_in: true,
+            });
+    }
+
+    fn get_bounce_count(app: &App) -> usize {
+        let events = app.world.get_resource::<Events<BallBouncedEvt>>().unwrap();
+        events.get_reader().iter(events).count()
+    }
+
+    #[test]
+    fn close_call_starts_review() {
+        let mut app = get_review_test_app();
+        send_review(&mut app);
+        app.update();
+
+        assert!(app.world.get_resource::<HawkEyeReview>().is_some());
+        assert_eq!(get_bounce_count(&app), 0);
+    }
+
+    #[test]
+    fn queued_transition_resolves_bounce_without_review() {
+        let mut app = get_review_test_app();
+        // e.g. the game was won on the same frame
+        app.world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .push(GameState::PerkSelection)
+            .unwrap();
+        send_review(&mut app);
+        app.update();
+
+        assert!(app.world.get_resource::<HawkEyeReview>().is_none());
+        assert_eq!(get_bounce_count(&app), 1);
+    }
+
+    fn get_test_court() -> CourtSettings {
+        CourtSettings {
+            left: -550.,
+            right: 550.,
+            top: 300.,
+            bottom: -300.,
+            base_region_size: Vec3::ONE,
+            region_x: 275.,
+        }
+    }
+
+    #[test]
+    fn close_call_is_measured_to_the_line_not_its_extension() {
+        let court_set = get_test_court();
+
+        assert!(is_close_call(Vec2::new(200., 305.), &court_set, 0., 12.));
+        // in line w/ the baseline, but way past the sideline
+        assert!(!is_close_call(Vec2::new(700., 305.), &court_set, 0., 12.));
+        // just around the corner is still close
+        assert!(is_close_call(Vec2::new(555., 305.), &court_set, 0., 12.));
+    }
+
+    #[test]
+    fn close_call_follows_the_out_margin() {
+        let court_set = get_test_court();
+
+        // close to the painted line, but the call is made 10 further out
+        assert!(!is_close_call(Vec2::new(542., 0.), &court_set, 10., 12.));
+        assert!(is_close_call(Vec2::new(565., 0.), &court_set, 10., 12.));
+        assert!(is_close_call(Vec2::new(542., 0.), &court_set, 0., 12.));
+    }
+}
@@ -1,11 +1,17 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_extensions::panic_on_error;
 use bevy_input::*;
 
 pub struct InputBindingPlugin;
 impl Plugin for InputBindingPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_startup_system(setup_bindings.chain(panic_on_error));
+        app.init_resource::<InputSettings>()
+            .init_resource::<MouseAimSettings>()
+            .init_resource::<AimSnapSettings>()
+            .init_resource::<DoubleTapDashSettings>()
+            .init_resource::<DashIntents>()
+            .add_startup_system(setup_bindings.chain(panic_on_error))
+            .add_system(detect_double_tap_dash.label(DOUBLE_TAP_DASH_LABEL));
     }
 }
 
@@ -15,6 +21,11 @@ pub enum InputAction {
     Dash,
     LockPosition,
     ChangePalette,
+    ChangePaletteMode,
+    ToggleDiagnostics,
+    ToggleServePractice,
+    ExportReplay,
+    ToggleFreeCamera,
     Reset,
 }
 
@@ -28,13 +39,137 @@ pub enum InputAxis {
 
 pub type PlayerInput = ActionInput<InputAction, InputAxis>;
 
+pub struct InputSettings {
+    pub deadzones: HashMap<InputAxis, f32>,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            deadzones: [
+                (InputAxis::MoveX, 0.15),
+                (InputAxis::MoveY, 0.15),
+                (InputAxis::AimX, 0.2),
+                (InputAxis::AimY, 0.2),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+impl InputSettings {
+    pub fn get_deadzone(&self, axis: &InputAxis) -> f32 {
+        self.deadzones.get(axis).copied().unwrap_or(0.)
+    }
+
+    // values inside the deadzone map to 0, the rest is rescaled to keep the full 0..1 range
+    pub fn apply_deadzone(&self, axis: &InputAxis, value: f32) -> f32 {
+        let deadzone = self.get_deadzone(axis).clamp(0., 0.99);
+
+        if value.abs() < deadzone {
+            0.
+        } else {
+            value.signum() * ((value.abs() - deadzone) / (1. - deadzone)).min(1.)
+        }
+    }
+
+    pub fn get_xy_axes(
+        &self,
+        input: &PlayerInput,
+        player_id: usize,
+        x_axis: &InputAxis,
+        y_axis: &InputAxis,
+    ) -> Vec2 {
+        let raw = input.get_xy_axes_raw(player_id, x_axis, y_axis);
+
+        Vec2::new(
+            self.apply_deadzone(x_axis, raw.x),
+            self.apply_deadzone(y_axis, raw.y),
+        )
+    }
+}
+
+// player 1 aims at the cursor & swings with the left mouse button, movement stays on the keyboard
+#[derive(Default)]
+pub struct MouseAimSettings {
+    pub enabled: bool,
+}
+
+pub const MOUSE_AIM_PLAYER_ID: usize = 1;
+
+// locks the aim to n evenly spaced directions (8 => cardinals & diagonals)
+#[derive(Clone, Copy)]
+pub struct AimSnap {
+    pub enabled: bool,
+    pub directions: u32,
+}
+
+impl Default for AimSnap {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            directions: 8,
+        }
+    }
+}
+
+// keyed by player id - keyboard & gamepad share the ids, so it's free aim for everyone by default
+#[derive(Default)]
+pub struct AimSnapSettings {
+    pub players: HashMap<usize, AimSnap>,
+}
+
+impl AimSnapSettings {
+    pub fn get_snap(&self, player_id: usize) -> Option<AimSnap> {
+        self.players
+            .get(&player_id)
+            .copied()
+            .filter(|snap| snap.enabled && snap.directions > 0)
+    }
+}
+
+// double-tapping a movement direction dashes that way
+// replaces the dash button while enabled, so the two can't both fire
+pub struct DoubleTapDashSettings {
+    pub enabled: bool,
+    pub window_sec: f32,
+}
+
+impl Default for DoubleTapDashSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_sec: 0.25,
+        }
+    }
+}
+
+pub const DOUBLE_TAP_DASH_LABEL: &str = "double_tap_dash";
+
+// dash dirs by player id, only valid for the frame they were detected in
+#[derive(Default)]
+pub struct DashIntents(pub HashMap<usize, Vec2>);
+
+#[derive(Default)]
+struct DoubleTapState {
+    last_dir: Vec2,
+    last_tap: Option<(Vec2, f64)>,
+}
+
+pub fn get_player_gamepad_id(player_id: usize) -> usize {
+    player_id - 1
+}
+
 fn setup_bindings(
     mut map: ResMut<ActionMap<InputAction, InputAxis>>,
     mut gamepad_map: ResMut<GamepadMap>,
 ) -> Result<(), BindingError> {
-    let deadzone = 0.15;
+    // deadzones are applied through InputSettings
+    let deadzone = 0.;
 
-    for id in 1..=2 {
+    // up to 4 gamepads for doubles
+    for id in 1..=4 {
         map.bind_button_action(id, InputAction::Dash, GamepadButtonType::RightTrigger)?
             .bind_button_action(id, InputAction::Dash, GamepadButtonType::RightTrigger2)?
             .bind_button_action(id, InputAction::Swing, GamepadButtonType::South)?
@@ -86,12 +221,17 @@ fn setup_bindings(
                 deadzone,
             );
 
-        gamepad_map.map_gamepad(id - 1, id);
+        gamepad_map.map_gamepad(get_player_gamepad_id(id), id);
     }
 
     map.bind_button_action(1, InputAction::Dash, KeyCode::Space)?
         .bind_button_action(1, InputAction::Swing, KeyCode::J)?
         .bind_button_action(1, InputAction::Reset, KeyCode::Escape)?
+        .bind_button_action(1, InputAction::ChangePaletteMode, KeyCode::C)?
+        .bind_button_action(1, InputAction::ToggleDiagnostics, KeyCode::F3)?
+        .bind_button_action(1, InputAction::ToggleServePractice, KeyCode::F4)?
+        .bind_button_action(1, InputAction::ExportReplay, KeyCode::F5)?
+        .bind_button_action(1, InputAction::ToggleFreeCamera, KeyCode::F6)?
         .bind_axis(
             1,
             InputAxis::MoveX,
@@ -118,3 +258,43 @@ fn setup_bindings(
         );
     Ok(())
 }
+
+fn detect_double_tap_dash(
+    input: Res<PlayerInput>,
+    input_settings: Res<InputSettings>,
+    settings: Res<DoubleTapDashSettings>,
+    time: Res<Time>,
+    mut intents: ResMut<DashIntents>,
+    mut states: Local<HashMap<usize, DoubleTapState>>,
+) {
+    intents.0.clear();
+
+    if !settings.enabled {
+        return;
+    }
+
+    let now = time.seconds_since_startup();
+
+    for id in 1..=4 {
+        let dir = input_settings.get_xy_axes(&input, id, &InputAxis::MoveX, &InputAxis::MoveY);
+        let state = states.entry(id).or_default();
+
+        // a tap is the move input leaving the neutral position
+        if dir != Vec2::ZERO && state.last_dir == Vec2::ZERO {
+            let tap_dir = dir.normalize();
+
+            match state.last_tap {
+                Some((prev_dir, tapped_at))
+                    if now - tapped_at <= settings.window_sec as f64
+                        && prev_dir.dot(tap_dir) > 0.9 =>
+                {
+                    intents.0.insert(id, tap_dir);
+                    state.last_tap = None;
+                }
+                _ => state.last_tap = Some((tap_dir, now)),
+            }
+        }
+
+        state.last_dir = dir;
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
+    ball::{BallHitEvt, BallStatus},
     extra::TransformBundle,
-    palette::PaletteColor,
+    game_speed::GameTime,
+    palette::{Palette, PaletteColor},
     physics::PhysLayer,
     render::{COURT_LINE_Z, COURT_Z, NET_Z, SHADOW_Z},
     reset::Persistent,
@@ -14,7 +16,7 @@ use bevy::{
 };
 use bevy_inspector_egui::Inspectable;
 use bevy_prototype_lyon::prelude::*;
-use bevy_tweening::{lens::TransformPositionLens, Animator, EaseFunction, Tween, TweeningType};
+use bevy_tweening::{lens::SpriteColorLens, Animator, EaseFunction, Tween, TweeningType};
 use heron::*;
 use rand::*;
 use std::{ops::RangeInclusive, time::Duration};
@@ -22,21 +24,49 @@ use std::{ops::RangeInclusive, time::Duration};
 pub struct LevelPlugin;
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.insert_resource(NetOffset(0.))
+        app.init_resource::<NetOffset>()
+            .init_resource::<NetSettings>()
             .add_startup_system(setup)
             .add_system(draw_court)
-            .add_system_set(SystemSet::on_update(GameState::Game).with_system(handle_net_offset));
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(handle_net_offset)
+                    .with_system(smooth_net_offset)
+                    .with_system(show_serve_target)
+                    .with_system(hide_serve_target),
+            );
     }
 }
 
 #[derive(Component)]
 pub struct Net;
 
-pub struct NetOffset(pub f32);
+#[derive(Default)]
+pub struct NetOffset {
+    // eased towards the target, used for anything the players can see or bump into
+    pub current_offset: f32,
+    pub target_offset: f32,
+}
+
+pub struct NetSettings {
+    // how quickly the net catches up to its target offset
+    pub offset_smoothing: f32,
+}
+
+impl Default for NetSettings {
+    fn default() -> Self {
+        Self {
+            offset_smoothing: 4.,
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct Court;
 
+#[derive(Component)]
+pub struct ServeTarget;
+
 #[derive(Component)]
 pub struct InitialRegion(pub CourtRegion);
 
@@ -52,6 +82,69 @@ pub struct CourtSettings {
     pub region_x: f32,
 }
 
+impl CourtSettings {
+    // positive margin grows the in-bounds area, negative shrinks it
+    pub fn is_out_of_bounds(&self, pos: Vec2, margin: f32) -> bool {
+        pos.x < self.left - margin
+            || pos.x > self.right + margin
+            || pos.y < self.bottom - margin
+            || pos.y > self.top + margin
+    }
+
+    // (min, max) of a court quadrant, the halves split at the net like the region colliders
+    pub fn get_region_bounds(&self, region: CourtRegion, net_x: f32) -> Option<(Vec2, Vec2)> {
+        if region.is_out_of_bounds() {
+            return None;
+        }
+
+        let min = Vec2::new(
+            if region.is_left() { self.left } else { net_x },
+            if region.is_top() { 0. } else { self.bottom },
+        );
+        let max = Vec2::new(
+            if region.is_left() { net_x } else { self.right },
+            if region.is_top() { self.top } else { 0. },
+        );
+
+        Some((min, max))
+    }
+
+    // same margin as is_out_of_bounds, but per quadrant
+    // it moves the baseline, the sidelines & the service line, but never the net
+    // the grown boxes overlap on the service line with a positive margin, `preferred` wins there
+    pub fn get_region(
+        &self,
+        pos: Vec2,
+        margin: f32,
+        net_x: f32,
+        preferred: Option<CourtRegion>,
+    ) -> CourtRegion {
+        preferred
+            .into_iter()
+            .chain([
+                CourtRegion::TopLeft,
+                CourtRegion::TopRight,
+                CourtRegion::BottomLeft,
+                CourtRegion::BottomRight,
+            ])
+            .find(|region| {
+                self.get_region_bounds(*region, net_x)
+                    .map_or(false, |(mut min, mut max)| {
+                        if region.is_left() {
+                            min.x -= margin;
+                        } else {
+                            max.x += margin;
+                        }
+                        min.y -= margin;
+                        max.y += margin;
+
+                        pos.cmpge(min).all() && pos.cmple(max).all()
+                    })
+            })
+            .unwrap_or(CourtRegion::OutOfBounds)
+    }
+}
+
 #[derive(Default, Component, Inspectable, Clone, Copy, Debug, PartialEq)]
 pub enum CourtRegion {
     #[default]
@@ -94,28 +187,28 @@ impl CourtRegion {
         }
     }
 
-    pub fn get_player_id(&self) -> usize {
-        if self.is_left() {
-            1
-        } else {
-            2
+    pub fn from_sides(is_left: bool, is_top: bool) -> Self {
+        match (is_left, is_top) {
+            (true, true) => CourtRegion::TopLeft,
+            (true, false) => CourtRegion::BottomLeft,
+            (false, true) => CourtRegion::TopRight,
+            (false, false) => CourtRegion::BottomRight,
         }
     }
 
-    pub fn get_random() -> Self {
-        Self::get_random_from_range(0..=3)
+    pub fn get_random(rng: &mut impl Rng) -> Self {
+        Self::get_random_from_range(rng, 0..=3)
     }
 
-    pub fn get_random_left() -> Self {
-        Self::get_random_from_range(0..=1)
+    pub fn get_random_left(rng: &mut impl Rng) -> Self {
+        Self::get_random_from_range(rng, 0..=1)
     }
 
-    pub fn get_random_right() -> Self {
-        Self::get_random_from_range(2..=3)
+    pub fn get_random_right(rng: &mut impl Rng) -> Self {
+        Self::get_random_from_range(rng, 2..=3)
     }
 
-    pub fn get_random_from_range(range: RangeInclusive<usize>) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn get_random_from_range(rng: &mut impl Rng, range: RangeInclusive<usize>) -> Self {
         [
             CourtRegion::TopLeft,
             CourtRegion::BottomLeft,
@@ -263,6 +356,20 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             .insert(Persistent);
     }
 
+    // highlights the service box the serve has to land in
+    commands
+        .spawn_bundle(SpriteBundle {
+            transform: Transform::from_xyz(0., 0., COURT_Z + 0.5),
+            sprite: Sprite {
+                color: Color::NONE,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(ServeTarget)
+        .insert(Name::new("ServeTarget"))
+        .insert(Persistent);
+
     // cheeky bg - maybe just set for camera?
     commands
         .spawn_bundle(SpriteBundle {
@@ -322,45 +429,110 @@ fn handle_net_offset(
     mut commands: Commands,
     score: Res<Score>,
     mut offset: ResMut<NetOffset>,
-    net_q: Query<(Entity, &Transform), With<Net>>,
     mut region_q: Query<(Entity, &CourtRegion, &mut Transform, &mut CollisionShape), Without<Net>>,
     settings: Res<CourtSettings>,
 ) {
     if score.is_changed() {
         let offset_mult = -50.;
-        offset.0 = (score.right_player.games as f32 - score.left_player.games as f32) * offset_mult;
+        offset.target_offset =
+            (score.right_player.games as f32 - score.left_player.games as f32) * offset_mult;
 
         if cfg!(feature = "debug") {
-            offset.0 =
+            offset.target_offset =
                 (score.right_player.points as f32 - score.left_player.points as f32) * offset_mult;
         }
 
-        // tween net
-        if let Ok((net_e, net_t)) = net_q.get_single() {
-            commands.entity(net_e).insert(Animator::new(Tween::new(
-                EaseFunction::QuadraticInOut,
-                TweeningType::Once,
-                Duration::from_millis(400),
-                TransformPositionLens {
-                    start: net_t.translation,
-                    end: Vec3::new(offset.0, net_t.translation.y, net_t.translation.z),
-                },
-            )));
-        }
-
         // resize regions
         for (region_e, region, region_t, _region_coll_shape) in region_q.iter_mut() {
             let x = if region.is_left() {
-                -settings.region_x + offset.0 / 2.
+                -settings.region_x + offset.target_offset / 2.
             } else {
-                settings.region_x + offset.0 / 2.
+                settings.region_x + offset.target_offset / 2.
             };
             let side_mult = if region.is_left() { 1. } else { -1. };
             let mut extends = settings.base_region_size;
-            extends.x += (offset.0 / 2.) * side_mult;
+            extends.x += (offset.target_offset / 2.) * side_mult;
             spawn_region(&mut commands, *region, x, region_t.translation.y, extends);
 
             commands.entity(region_e).despawn_recursive();
         }
     }
 }
+
+// nice2have: resize the regions along with the net
+fn smooth_net_offset(
+    mut offset: ResMut<NetOffset>,
+    mut net_q: Query<&mut Transform, With<Net>>,
+    settings: Res<NetSettings>,
+    time: GameTime,
+) {
+    let t = (settings.offset_smoothing * time.scaled_delta_seconds()).min(1.);
+    offset.current_offset += (offset.target_offset - offset.current_offset) * t;
+
+    for mut net_t in net_q.iter_mut() {
+        net_t.translation.x = offset.current_offset;
+    }
+}
+
+fn show_serve_target(
+    mut commands: Commands,
+    ball_q: Query<&BallStatus, Added<BallStatus>>,
+    region_q: Query<(&CourtRegion, &Transform, &CollisionShape)>,
+    mut target_q: Query<
+        (Entity, &mut Transform, &mut Sprite),
+        (With<ServeTarget>, Without<CourtRegion>),
+    >,
+    palette: Res<Palette>,
+) {
+    for status in ball_q.iter() {
+        if let BallStatus::Serve(serve_region, ..) = status {
+            let target_region = serve_region.get_inverse();
+
+            if let Some((_, region_t, region_shape)) =
+                region_q.iter().find(|(r, ..)| Some(**r) == target_region)
+            {
+                if let (
+                    Ok((target_e, mut target_t, mut target_sprite)),
+                    CollisionShape::Cuboid { half_extends, .. },
+                ) = (target_q.get_single_mut(), region_shape)
+                {
+                    target_t.translation.x = region_t.translation.x;
+                    target_t.translation.y = region_t.translation.y;
+                    target_sprite.custom_size = Some(half_extends.truncate() * 2.);
+
+                    commands.entity(target_e).insert(Animator::new(Tween::new(
+                        EaseFunction::QuadraticOut,
+                        TweeningType::Once,
+                        Duration::from_millis(400),
+                        SpriteColorLens {
+                            start: target_sprite.color,
+                            end: palette.get_color(&PaletteColor::ServeTarget),
+                        },
+                    )));
+                }
+            }
+        }
+    }
+}
+
+fn hide_serve_target(
+    mut commands: Commands,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    target_q: Query<(Entity, &Sprite), With<ServeTarget>>,
+) {
+    if ev_r_hit.iter().next().is_some() {
+        for (target_e, target_sprite) in target_q.iter() {
+            if target_sprite.color.a() > 0. {
+                commands.entity(target_e).insert(Animator::new(Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(300),
+                    SpriteColorLens {
+                        start: target_sprite.color,
+                        end: Color::NONE,
+                    },
+                )));
+            }
+        }
+    }
+}
@@ -7,56 +7,110 @@
 use ai_player_controller::AiPlayerControllerPlugin;
 use animation::AnimationPlugin;
 use asset::AssetPlugin;
+use attract::AttractPlugin;
 use ball::BallPlugin;
+use ball_prediction::BallPredictionPlugin;
 use bevy::prelude::*;
 use bevy_input::ActionInputPlugin;
 use bevy_prototype_lyon::plugin::ShapePlugin;
 use bevy_time::TimePlugin;
-use bevy_tweening::TweeningPlugin;
 use big_brain::BigBrainPlugin;
 use camera::CameraPlugin;
+use clutch::ClutchPlugin;
+use combo::ComboPlugin;
+use countdown::CountdownPlugin;
+use crowd::CrowdPlugin;
 use debug::DebugPlugin;
+use ends::EndsPlugin;
+use fault::FaultPlugin;
+use game_speed::GameSpeedPlugin;
+use hawk_eye::HawkEyePlugin;
 use heron::*;
 use input_binding::{InputAction, InputAxis, InputBindingPlugin};
 use level::{CourtRegion, InitialRegion, LevelPlugin};
+use match_config::{GameMode, MatchConfig};
+use menu::MenuPlugin;
+use overlay::OverlayPlugin;
 use palette::PalettePlugin;
+use particle::ParticlePlugin;
+use perk::PerkPlugin;
 use player::PlayerPlugin;
 use player_action::PlayerActionPlugin;
 use player_animation::PlayerAnimationPlugin;
 use player_controller::PlayerControllerPlugin;
+use power_bar::PowerBarPlugin;
+use practice::PracticePlugin;
+use rally::RallyPlugin;
+use replay::ReplayPlugin;
 use reset::ResetPlugin;
+use results::ResultsPlugin;
+use rng::RngPlugin;
+use rumble::RumblePlugin;
 use score::ScorePlugin;
+use serve::ServePlugin;
+use sfx::SfxPlugin;
 use trail::TrailPlugin;
+use warm_up::WarmUpPlugin;
 use window::{WIN_HEIGHT, WIN_WIDTH};
 
 // todo: namespace modules (e.g. player)
 mod ai_player_controller;
 mod animation;
 mod asset;
+mod attract;
 mod ball;
+mod ball_prediction;
 mod camera;
+mod clutch;
+mod combo;
+mod countdown;
+mod crowd;
 mod debug;
+mod ends;
 mod extra;
+mod fault;
+mod game_speed;
+mod hawk_eye;
 mod input_binding;
 mod level;
+mod match_config;
+mod menu;
+mod overlay;
 mod palette;
+mod particle;
+mod perk;
 mod physics;
 mod player;
 mod player_action;
 mod player_animation;
 mod player_controller;
+mod power_bar;
+mod practice;
+mod rally;
 mod render;
+mod replay;
 mod reset;
+mod results;
+mod rng;
+mod rumble;
 mod score;
+mod serve;
+mod sfx;
 mod trail;
+mod warm_up;
 mod window;
 
 const NAME: &str = "Tag of Ball";
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 enum GameState {
+    Menu,
     Game,
+    PerkSelection,
+    // close call review pushed over the game
+    HawkEye,
     Reset,
+    Results,
 }
 
 #[derive(SystemLabel, Debug, Clone, Eq, PartialEq, Hash)]
@@ -89,11 +143,12 @@ fn main() {
         .insert_resource(ClearColor(Color::WHITE))
         // game resources
         .insert_resource(InitialRegion(region))
+        .init_resource::<MatchConfig>()
+        .init_resource::<GameMode>()
         // bevy plugins
         .add_plugins(DefaultPlugins)
         // 3rd party crates
         .add_plugin(PhysicsPlugin::default())
-        .add_plugin(TweeningPlugin)
         .add_plugin(BigBrainPlugin)
         // game crates
         .add_plugin(TimePlugin)
@@ -102,20 +157,44 @@ fn main() {
         .add_plugin(AiPlayerControllerPlugin)
         .add_plugin(AnimationPlugin)
         .add_plugin(AssetPlugin)
+        .add_plugin(AttractPlugin)
         .add_plugin(BallPlugin)
+        .add_plugin(BallPredictionPlugin)
         .add_plugin(CameraPlugin)
+        .add_plugin(ClutchPlugin)
+        .add_plugin(ComboPlugin)
+        .add_plugin(CountdownPlugin)
+        .add_plugin(CrowdPlugin)
+        .add_plugin(EndsPlugin)
+        .add_plugin(FaultPlugin)
+        .add_plugin(GameSpeedPlugin)
+        .add_plugin(HawkEyePlugin)
         .add_plugin(InputBindingPlugin)
         .add_plugin(LevelPlugin)
+        .add_plugin(MenuPlugin)
+        .add_plugin(OverlayPlugin)
         .add_plugin(PalettePlugin)
+        .add_plugin(ParticlePlugin)
+        .add_plugin(PerkPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(PlayerControllerPlugin)
         .add_plugin(PlayerActionPlugin)
         .add_plugin(PlayerAnimationPlugin)
+        .add_plugin(PowerBarPlugin)
+        .add_plugin(PracticePlugin)
+        .add_plugin(RallyPlugin)
+        .add_plugin(ReplayPlugin)
         .add_plugin(ResetPlugin)
+        .add_plugin(ResultsPlugin)
+        .add_plugin(RngPlugin)
+        .add_plugin(RumblePlugin)
         .add_plugin(ScorePlugin)
+        .add_plugin(ServePlugin)
+        .add_plugin(SfxPlugin)
         .add_plugin(TrailPlugin)
+        .add_plugin(WarmUpPlugin)
         // initial state
-        .add_state(GameState::Game);
+        .add_state(GameState::Menu);
 
     if cfg!(feature = "debug") {
         app.add_plugin(DebugPlugin);
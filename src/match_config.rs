@@ -0,0 +1,202 @@
+#[derive(Clone)]
+pub struct MatchConfig {
+    pub deuce_rule: DeuceRule,
+    pub perk_picker: PerkPicker,
+    pub fault_limit: FaultLimit,
+    // bounces allowed before the receiver loses the point - 2 for casual play
+    pub bounce_limit: usize,
+    // scoring only, the court lines stay put - positive is more forgiving
+    pub out_margin: f32,
+    pub net_cord_enabled: bool,
+    pub net_cord_chance: f32,
+    pub serve_net_rule: ServeNetRule,
+    // serves have to land in the diagonal service box, the whole receiver's half is fine otherwise
+    pub strict_serve_box: bool,
+    pub serve_rotation: ServeRotation,
+    pub games_to_win: u8,
+    // players change ends every n games played, 0 to never change
+    pub change_ends_games: u8,
+    pub scoring_mode: ScoringMode,
+    // only used by ScoringMode::FirstTo
+    pub win_by_two: bool,
+    // 2v2 - teams are split by is_left_player_id
+    pub doubles: bool,
+    // distance from the horizontal center line, the serve region picks the sign
+    pub player_spawn_y: f32,
+    // seeds GameRng on match start
+    // can be set with `--seed <n>` to share a matchup
+    pub seed: u64,
+}
+
+impl Default for MatchConfig {
+    fn default() -> Self {
+        Self {
+            deuce_rule: DeuceRule::default(),
+            perk_picker: PerkPicker::default(),
+            fault_limit: FaultLimit::default(),
+            bounce_limit: 1,
+            out_margin: 0.,
+            net_cord_enabled: true,
+            net_cord_chance: 0.15,
+            serve_net_rule: ServeNetRule::default(),
+            strict_serve_box: true,
+            serve_rotation: ServeRotation::default(),
+            games_to_win: 3,
+            change_ends_games: 0,
+            scoring_mode: ScoringMode::default(),
+            win_by_two: false,
+            doubles: false,
+            player_spawn_y: 150.,
+            seed: get_seed_arg().unwrap_or_else(rand::random),
+        }
+    }
+}
+
+impl MatchConfig {
+    pub fn get_player_count(&self) -> usize {
+        if self.doubles {
+            4
+        } else {
+            2
+        }
+    }
+}
+
+fn get_seed_arg() -> Option<u64> {
+    let mut args = std::env::args().skip_while(|a| a != "--seed").skip(1);
+    args.next().and_then(|seed| seed.parse().ok())
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameMode {
+    // player 2 is the AI
+    Solo,
+    Local2P,
+    // AI vs AI demo once the menu sits idle, see attract.rs
+    Attract,
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        if cfg!(feature = "debug") {
+            GameMode::Solo
+        } else {
+            GameMode::Local2P
+        }
+    }
+}
+
+impl GameMode {
+    pub fn is_ai_player(&self, player_id: usize) -> bool {
+        match self {
+            GameMode::Solo => player_id == 2,
+            GameMode::Local2P => false,
+            GameMode::Attract => true,
+        }
+    }
+
+    // the attract demo isn't pickable
+    pub fn next(&self) -> Self {
+        match self {
+            GameMode::Solo => GameMode::Local2P,
+            GameMode::Local2P | GameMode::Attract => GameMode::Solo,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            GameMode::Solo => "Solo",
+            GameMode::Local2P => "2 Players",
+            GameMode::Attract => "Demo",
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum DeuceRule {
+    #[default]
+    Advantage,
+    // no-ad scoring - the next point at deuce wins the game
+    GoldenPoint,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ScoringMode {
+    #[default]
+    Tennis,
+    // quick play - raw points, no games
+    FirstTo(u32),
+}
+
+// what happens when a serve clips the net & lands in
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ServeNetRule {
+    // replay the serve
+    #[default]
+    Let,
+    // arcade rule
+    Fault,
+}
+
+// when the serve goes to the other side, see score::should_swap_serve
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ServeRotation {
+    // by game in tennis scoring, every 2 points in quick play
+    #[default]
+    Auto,
+    EveryPoint,
+    // quick play has no games, so the serve never changes there
+    EveryGame,
+    // the first server serves once, then it alternates every 2 points
+    Tiebreak,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum PerkPicker {
+    Disabled,
+    #[default]
+    Winner,
+    Loser,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum FaultLimit {
+    // a single fault loses the point
+    FirstServeOnly,
+    #[default]
+    TwoServes,
+    // keep serving until the serve goes in
+    FirstIn,
+}
+
+impl FaultLimit {
+    pub fn is_reached(&self, fault_count: u8) -> bool {
+        match self {
+            FaultLimit::FirstServeOnly => fault_count >= 1,
+            FaultLimit::TwoServes => fault_count >= 2,
+            FaultLimit::FirstIn => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attract_mode_is_all_ai() {
+        assert!((1..=4).all(|id| GameMode::Attract.is_ai_player(id)));
+        assert!(!GameMode::Solo.is_ai_player(1));
+        assert_eq!(GameMode::Attract.next(), GameMode::Solo);
+        assert_ne!(GameMode::Local2P.next(), GameMode::Attract);
+    }
+
+    #[test]
+    fn fault_limit_is_reached() {
+        assert!(!FaultLimit::FirstServeOnly.is_reached(0));
+        assert!(FaultLimit::FirstServeOnly.is_reached(1));
+        assert!(!FaultLimit::TwoServes.is_reached(1));
+        assert!(FaultLimit::TwoServes.is_reached(2));
+        assert!(!FaultLimit::FirstIn.is_reached(u8::MAX));
+    }
+}
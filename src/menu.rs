@@ -0,0 +1,224 @@
+use crate::{
+    ai_player_controller::AiDifficulty,
+    animation::ReduceMotion,
+    game_speed::GameSpeed,
+    input_binding::{InputAction, InputAxis, MouseAimSettings, PlayerInput},
+    match_config::GameMode,
+    palette::{PaletteColor, PaletteTheme},
+    player::AimAssist,
+    GameState,
+};
+use bevy::{app::AppExit, prelude::*};
+
+pub const MENU_INPUT_LABEL: &str = "menu_input";
+
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_system_set(SystemSet::on_enter(GameState::Menu).with_system(setup_ui))
+            .add_system_set(
+                SystemSet::on_update(GameState::Menu)
+                    .with_system(handle_menu_input.label(MENU_INPUT_LABEL))
+                    .with_system(update_ui),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Menu).with_system(cleanup_ui));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MenuItem {
+    Play,
+    Mode,
+    AiDifficulty,
+    Speed,
+    Theme,
+    ReduceMotion,
+    MouseAim,
+    AimAssist,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 9] = [
+        MenuItem::Play,
+        MenuItem::Mode,
+        MenuItem::AiDifficulty,
+        MenuItem::Speed,
+        MenuItem::Theme,
+        MenuItem::ReduceMotion,
+        MenuItem::MouseAim,
+        MenuItem::AimAssist,
+        MenuItem::Quit,
+    ];
+}
+
+struct Menu {
+    selected: usize,
+    axis_released: bool,
+}
+
+#[derive(Component)]
+struct MenuText;
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Menu {
+        selected: 0,
+        axis_released: false,
+    });
+
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                margin: Rect::all(Val::Auto),
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(MenuText)
+        .insert(Name::new("MenuText"));
+}
+
+fn handle_menu_input(
+    mut input: ResMut<PlayerInput>,
+    menu: Option<ResMut<Menu>>,
+    mut state: ResMut<State<GameState>>,
+    mut theme: ResMut<PaletteTheme>,
+    mut game_mode: ResMut<GameMode>,
+    mut ai_difficulty: ResMut<AiDifficulty>,
+    mut game_speed: ResMut<GameSpeed>,
+    mut reduce_motion: ResMut<ReduceMotion>,
+    mut mouse_aim: ResMut<MouseAimSettings>,
+    mut aim_assist: ResMut<AimAssist>,
+    mut ev_w_exit: EventWriter<AppExit>,
+) {
+    let mut menu = match menu {
+        Some(menu) => menu,
+        None => return,
+    };
+
+    for id in 1..=4 {
+        let y = input
+            .get_xy_axes_raw(id, &InputAxis::MoveX, &InputAxis::MoveY)
+            .y;
+
+        if y.abs() >= 0.5 {
+            if menu.axis_released {
+                menu.axis_released = false;
+                // positive y is up
+                menu.selected = if y > 0. {
+                    menu.selected.saturating_sub(1)
+                } else {
+                    (menu.selected + 1).min(MenuItem::ALL.len() - 1)
+                };
+            }
+
+            return;
+        }
+
+        if input.just_pressed(id, InputAction::Swing) {
+            input.use_button_action(id, InputAction::Swing);
+
+            match MenuItem::ALL[menu.selected] {
+                MenuItem::Play => {
+                    if let Err(e) = state.set(GameState::Game) {
+                        warn!("Failed to start the game: {:?}", e);
+                    }
+                }
+                MenuItem::Mode => *game_mode = game_mode.next(),
+                MenuItem::AiDifficulty => *ai_difficulty = ai_difficulty.next(),
+                MenuItem::Speed => {
+                    let next = game_speed.next();
+                    game_speed.set(next);
+                }
+                MenuItem::Theme => *theme = theme.next(),
+                MenuItem::ReduceMotion => reduce_motion.enabled = !reduce_motion.enabled,
+                MenuItem::MouseAim => mouse_aim.enabled = !mouse_aim.enabled,
+                MenuItem::AimAssist => aim_assist.enabled = !aim_assist.enabled,
+                MenuItem::Quit => ev_w_exit.send(AppExit),
+            }
+
+            return;
+        }
+    }
+
+    menu.axis_released = true;
+}
+
+fn update_ui(
+    menu: Option<Res<Menu>>,
+    theme: Res<PaletteTheme>,
+    game_mode: Res<GameMode>,
+    ai_difficulty: Res<AiDifficulty>,
+    game_speed: Res<GameSpeed>,
+    reduce_motion: Res<ReduceMotion>,
+    mouse_aim: Res<MouseAimSettings>,
+    aim_assist: Res<AimAssist>,
+    mut text_q: Query<&mut Text, With<MenuText>>,
+) {
+    let menu = match menu {
+        Some(menu) => menu,
+        None => return,
+    };
+    let items: Vec<String> = MenuItem::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let label = match item {
+                MenuItem::Play => "Play".to_string(),
+                MenuItem::Mode => format!("Mode: {}", game_mode.get_name()),
+                MenuItem::AiDifficulty => format!("AI: {}", ai_difficulty.get_name()),
+                MenuItem::Speed => format!("Speed: {:.2}x", game_speed.get()),
+                MenuItem::Theme => format!("Theme: {}", theme.get_name()),
+                MenuItem::ReduceMotion => format!(
+                    "Reduce motion: {}",
+                    if reduce_motion.enabled { "On" } else { "Off" }
+                ),
+                MenuItem::MouseAim => format!(
+                    "P1 aim: {}",
+                    if mouse_aim.enabled {
+                        "Mouse"
+                    } else {
+                        "Keys/Pad"
+                    }
+                ),
+                MenuItem::AimAssist => format!(
+                    "Aim assist: {}",
+                    if aim_assist.enabled { "On" } else { "Off" }
+                ),
+                MenuItem::Quit => "Quit".to_string(),
+            };
+
+            if i == menu.selected {
+                format!("[{}]", label)
+            } else {
+                label
+            }
+        })
+        .collect();
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = format!("{}\n\n{}", crate::NAME, items.join("\n"));
+    }
+}
+
+fn cleanup_ui(mut commands: Commands, text_q: Query<Entity, With<MenuText>>) {
+    for e in text_q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+
+    commands.remove_resource::<Menu>();
+}
@@ -0,0 +1,122 @@
+use crate::{
+    ball::Ball,
+    game_speed::GameTime,
+    input_binding::{InputAction, PlayerInput},
+    match_config::MatchConfig,
+    palette::PaletteColor,
+    player::{Player, PlayerSwing},
+    player_action::PlayerActionStatus,
+    reset::Persistent,
+};
+use bevy::{
+    diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+// available outside of the debug feature for tuning
+pub struct OverlayPlugin;
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_plugin(FrameTimeDiagnosticsPlugin::default())
+            .add_startup_system(setup)
+            .add_system(toggle_overlay)
+            .add_system(update_overlay);
+    }
+}
+
+#[derive(Component)]
+struct DiagnosticsText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(20.),
+                    top: Val::Px(20.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                },
+                Default::default(),
+            ),
+            visibility: Visibility { is_visible: false },
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(DiagnosticsText)
+        .insert(Name::new("DiagnosticsText"))
+        .insert(Persistent);
+}
+
+fn toggle_overlay(
+    input: Res<PlayerInput>,
+    mut text_q: Query<&mut Visibility, With<DiagnosticsText>>,
+) {
+    for id in 1..=4 {
+        if input.just_pressed(id, InputAction::ToggleDiagnostics) {
+            for mut visibility in text_q.iter_mut() {
+                visibility.is_visible = !visibility.is_visible;
+            }
+
+            break;
+        }
+    }
+}
+
+fn update_overlay(
+    mut text_q: Query<(&mut Text, &Visibility), With<DiagnosticsText>>,
+    ball_q: Query<&Ball>,
+    player_q: Query<(&Player, &PlayerSwing)>,
+    config: Res<MatchConfig>,
+    diagnostics: Res<Diagnostics>,
+    time: Res<Time>,
+    scaled_time: GameTime,
+) {
+    for (mut text, visibility) in text_q.iter_mut() {
+        if !visibility.is_visible {
+            continue;
+        }
+
+        let fps = diagnostics
+            .get(FrameTimeDiagnosticsPlugin::FPS)
+            .and_then(|fps| fps.average())
+            .unwrap_or(0.);
+        let time_scale = if time.delta_seconds() > 0. {
+            scaled_time.scaled_delta_seconds() / time.delta_seconds()
+        } else {
+            0.
+        };
+
+        let mut lines = vec![
+            format!("fps: {:.0}", fps),
+            format!("time scale: {:.2}", time_scale),
+            format!("seed: {}", config.seed),
+        ];
+
+        for (i, ball) in ball_q.iter().enumerate() {
+            lines.push(format!("ball {} speed: {:.0}", i, ball.speed));
+        }
+
+        let mut players: Vec<_> = player_q.iter().collect();
+        players.sort_by_key(|(p, _)| p.id);
+        for (player, swing) in players {
+            let cooldown = if let PlayerActionStatus::Cooldown = swing.status {
+                (swing.timer.duration() - swing.timer.elapsed()).as_secs_f32()
+            } else {
+                0.
+            };
+            lines.push(format!("p{} swing cooldown: {:.2}", player.id, cooldown));
+        }
+
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -7,8 +7,10 @@ use bevy_tweening::{
 use rand::random;
 
 use crate::{
+    ball::{Ball, BallHitEvt},
     input_binding::{InputAction, PlayerInput},
     level::Court,
+    player::is_left_player_id,
     trail::Trail,
 };
 
@@ -23,14 +25,80 @@ impl Plugin for PalettePlugin {
             .add_system(on_trail_added)
             .add_system(on_court_added)
             .add_system(handle_palette_input)
-            .insert_resource(if random::<bool>() {
-                CLAY_PALETTE
-            } else {
-                GRASS_PALETTE
-            });
+            .add_system(update_palette)
+            .add_system(tint_trail_on_hit);
+
+        // grass or clay
+        let theme = PaletteTheme(random::<usize>() % 2);
+        app.insert_resource(theme)
+            .init_resource::<PaletteMode>()
+            .init_resource::<PlayerAccents>()
+            .insert_resource(theme.get_palette());
     }
 }
 
+// new themes only need a palette table and an entry here
+pub const THEMES: [(&str, Palette); 3] = [
+    ("Grass", GRASS_PALETTE),
+    ("Clay", CLAY_PALETTE),
+    ("Night", NIGHT_PALETTE),
+];
+
+// index into THEMES
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PaletteTheme(pub usize);
+
+impl PaletteTheme {
+    pub fn get_name(&self) -> &'static str {
+        THEMES[self.0 % THEMES.len()].0
+    }
+
+    pub fn get_palette(&self) -> Palette {
+        THEMES[self.0 % THEMES.len()].1
+    }
+
+    pub fn next(&self) -> Self {
+        Self((self.0 + 1) % THEMES.len())
+    }
+}
+
+// accessibility palettes override the theme
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum PaletteMode {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    HighContrast,
+}
+
+impl PaletteMode {
+    pub fn get_palette(&self) -> Option<Palette> {
+        match self {
+            PaletteMode::Default => None,
+            PaletteMode::Deuteranopia => Some(DEUTERANOPIA_PALETTE),
+            PaletteMode::Protanopia => Some(PROTANOPIA_PALETTE),
+            PaletteMode::HighContrast => Some(HIGH_CONTRAST_PALETTE),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            PaletteMode::Default => PaletteMode::Deuteranopia,
+            PaletteMode::Deuteranopia => PaletteMode::Protanopia,
+            PaletteMode::Protanopia => PaletteMode::HighContrast,
+            PaletteMode::HighContrast => PaletteMode::Default,
+        }
+    }
+}
+
+// overrides the palette's player accents (e.g. picked before the match)
+#[derive(Default)]
+pub struct PlayerAccents {
+    pub left_player: Option<RgbColor>,
+    pub right_player: Option<RgbColor>,
+}
+
 // I was just lazy to redo the color in smt. that allows const (which rgba_u8 does not)
 #[derive(Clone, Copy, PartialEq)]
 pub struct RgbColor {
@@ -55,6 +123,7 @@ impl From<RgbColor> for Color {
     }
 }
 
+#[derive(Clone, Copy)]
 pub struct Palette {
     background: RgbColor,
     court: RgbColor,
@@ -62,12 +131,14 @@ pub struct Palette {
     court_pickets: RgbColor,
     ball: RgbColor,
     ball_trail: RgbColor,
-    player: RgbColor,
+    player_accents: [RgbColor; 2],
     player_aim: RgbColor,
     player_face: RgbColor,
     player_charge: RgbColor,
     score_text: RgbColor,
     shadow: RgbColor,
+    serve_target: RgbColor,
+    ball_arc: RgbColor,
 }
 
 impl Palette {
@@ -79,12 +150,16 @@ impl Palette {
             PaletteColor::CourtPost => self.court_pickets.into(),
             PaletteColor::Ball => self.ball.into(),
             PaletteColor::BallTrail => self.ball_trail.into(),
-            PaletteColor::Player => self.player.into(),
+            PaletteColor::PlayerAccent(id) => {
+                self.player_accents[if is_left_player_id(*id) { 0 } else { 1 }].into()
+            }
             PaletteColor::PlayerAim => self.player_aim.into(),
             PaletteColor::PlayerFace => self.player_face.into(),
             PaletteColor::PlayerCharge => self.player_charge.into(),
             PaletteColor::Text => self.score_text.into(),
             PaletteColor::Shadow => self.shadow.into(),
+            PaletteColor::ServeTarget => self.serve_target.into(),
+            PaletteColor::BallArc => self.ball_arc.into(),
         }
     }
 }
@@ -98,12 +173,15 @@ pub const GRASS_PALETTE: Palette = Palette {
     court_pickets: RgbColor::new(109, 141, 138),
     ball: RgbColor::new(229, 176, 131),
     ball_trail: RgbColor::new(246, 237, 205),
-    player: RgbColor::new(251, 247, 243),
+    // left & right player
+    player_accents: [RgbColor::new(251, 247, 243), RgbColor::new(236, 170, 140)],
     player_aim: RgbColor::new(251, 247, 243),
     player_face: RgbColor::new(32, 40, 61),
     player_charge: RgbColor::new(109, 141, 138),
     score_text: RgbColor::new(251, 247, 243),
     shadow: RgbColor::new_with_alpha(0, 8, 24, 80),
+    serve_target: RgbColor::new_with_alpha(251, 247, 243, 50),
+    ball_arc: RgbColor::new_with_alpha(229, 176, 131, 110),
 };
 
 // based on
@@ -115,12 +193,89 @@ pub const CLAY_PALETTE: Palette = Palette {
     court_pickets: RgbColor::new(203, 129, 117),
     ball: RgbColor::new(109, 141, 138),
     ball_trail: RgbColor::new(168, 200, 166),
-    player: RgbColor::new(246, 237, 205),
+    // left & right player
+    player_accents: [RgbColor::new(246, 237, 205), RgbColor::new(180, 210, 230)],
     player_aim: RgbColor::new(246, 237, 205),
     player_face: RgbColor::new(101, 80, 87),
     player_charge: RgbColor::new(203, 129, 117),
     score_text: RgbColor::new(246, 237, 205),
     shadow: RgbColor::new_with_alpha(22, 12, 0, 80),
+    serve_target: RgbColor::new_with_alpha(246, 237, 205, 60),
+    ball_arc: RgbColor::new_with_alpha(109, 141, 138, 110),
+};
+
+pub const NIGHT_PALETTE: Palette = Palette {
+    background: RgbColor::new(13, 17, 33),
+    court: RgbColor::new(35, 52, 89),
+    court_lines: RgbColor::new(196, 216, 242),
+    court_pickets: RgbColor::new(72, 94, 140),
+    ball: RgbColor::new(227, 240, 120),
+    ball_trail: RgbColor::new(140, 170, 220),
+    // left & right player
+    player_accents: [RgbColor::new(196, 216, 242), RgbColor::new(240, 180, 200)],
+    player_aim: RgbColor::new(196, 216, 242),
+    player_face: RgbColor::new(13, 17, 33),
+    player_charge: RgbColor::new(72, 94, 140),
+    score_text: RgbColor::new(196, 216, 242),
+    shadow: RgbColor::new_with_alpha(0, 0, 8, 110),
+    serve_target: RgbColor::new_with_alpha(196, 216, 242, 45),
+    ball_arc: RgbColor::new_with_alpha(227, 240, 120, 110),
+};
+
+// based on
+// https://jfly.uni-koeln.de/color/ (Okabe-Ito)
+pub const DEUTERANOPIA_PALETTE: Palette = Palette {
+    background: RgbColor::new(24, 28, 44),
+    court: RgbColor::new(0, 114, 178),
+    court_lines: RgbColor::new(250, 250, 250),
+    court_pickets: RgbColor::new(86, 180, 233),
+    ball: RgbColor::new(240, 228, 66),
+    ball_trail: RgbColor::new(230, 159, 0),
+    // left & right player
+    player_accents: [RgbColor::new(250, 250, 250), RgbColor::new(230, 159, 0)],
+    player_aim: RgbColor::new(230, 159, 0),
+    player_face: RgbColor::new(24, 28, 44),
+    player_charge: RgbColor::new(86, 180, 233),
+    score_text: RgbColor::new(250, 250, 250),
+    shadow: RgbColor::new_with_alpha(0, 0, 16, 90),
+    serve_target: RgbColor::new_with_alpha(240, 228, 66, 60),
+    ball_arc: RgbColor::new_with_alpha(240, 228, 66, 110),
+};
+
+pub const PROTANOPIA_PALETTE: Palette = Palette {
+    background: RgbColor::new(28, 28, 36),
+    court: RgbColor::new(0, 158, 115),
+    court_lines: RgbColor::new(250, 250, 250),
+    court_pickets: RgbColor::new(86, 180, 233),
+    ball: RgbColor::new(240, 228, 66),
+    ball_trail: RgbColor::new(204, 121, 167),
+    // left & right player
+    player_accents: [RgbColor::new(250, 250, 250), RgbColor::new(86, 180, 233)],
+    player_aim: RgbColor::new(0, 114, 178),
+    player_face: RgbColor::new(28, 28, 36),
+    player_charge: RgbColor::new(204, 121, 167),
+    score_text: RgbColor::new(250, 250, 250),
+    shadow: RgbColor::new_with_alpha(0, 0, 16, 90),
+    serve_target: RgbColor::new_with_alpha(240, 228, 66, 60),
+    ball_arc: RgbColor::new_with_alpha(240, 228, 66, 110),
+};
+
+pub const HIGH_CONTRAST_PALETTE: Palette = Palette {
+    background: RgbColor::new(0, 0, 0),
+    court: RgbColor::new(30, 30, 30),
+    court_lines: RgbColor::new(255, 255, 255),
+    court_pickets: RgbColor::new(160, 160, 160),
+    ball: RgbColor::new(255, 230, 0),
+    ball_trail: RgbColor::new(255, 255, 255),
+    // left & right player
+    player_accents: [RgbColor::new(255, 255, 255), RgbColor::new(255, 128, 0)],
+    player_aim: RgbColor::new(0, 255, 255),
+    player_face: RgbColor::new(0, 0, 0),
+    player_charge: RgbColor::new(255, 0, 255),
+    score_text: RgbColor::new(255, 255, 255),
+    shadow: RgbColor::new_with_alpha(0, 0, 0, 160),
+    serve_target: RgbColor::new_with_alpha(255, 230, 0, 70),
+    ball_arc: RgbColor::new_with_alpha(255, 230, 0, 110),
 };
 
 #[derive(Component, Clone, Copy)]
@@ -131,12 +286,14 @@ pub enum PaletteColor {
     CourtPost,
     Ball,
     BallTrail,
-    Player,
+    PlayerAccent(usize),
     PlayerAim,
     PlayerFace,
     PlayerCharge,
     Text,
     Shadow,
+    ServeTarget,
+    BallArc,
 }
 
 fn on_palette_changed(
@@ -205,17 +362,67 @@ fn on_court_added(palette: Res<Palette>, mut q: Query<&mut DrawMode, With<Court>
     }
 }
 
-fn handle_palette_input(mut palette: ResMut<Palette>, input: Res<PlayerInput>) {
+fn handle_palette_input(
+    mut theme: ResMut<PaletteTheme>,
+    mut mode: ResMut<PaletteMode>,
+    input: Res<PlayerInput>,
+) {
     for id in 0..=4 {
         if input.just_pressed(id, InputAction::ChangePalette) {
-            let is_grass = palette.background == GRASS_PALETTE.background;
-            *palette = if is_grass {
-                CLAY_PALETTE
-            } else {
-                GRASS_PALETTE
-            };
+            *theme = theme.next();
+            debug!("Palette theme: {}", theme.get_name());
+            break;
+        }
 
+        if input.just_pressed(id, InputAction::ChangePaletteMode) {
+            *mode = mode.next();
             break;
         }
     }
 }
+
+fn update_palette(
+    theme: Res<PaletteTheme>,
+    mode: Res<PaletteMode>,
+    accents: Res<PlayerAccents>,
+    mut palette: ResMut<Palette>,
+) {
+    if theme.is_changed() || mode.is_changed() || accents.is_changed() {
+        let mut new_palette = mode.get_palette().unwrap_or_else(|| theme.get_palette());
+
+        for (i, accent) in [accents.left_player, accents.right_player]
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(accent) = accent {
+                new_palette.player_accents[i] = accent;
+            }
+        }
+
+        *palette = new_palette;
+    }
+}
+
+// the trail takes the accent of whoever hit the ball last, shifted towards the ball color as the ball heats up
+fn tint_trail_on_hit(
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    ball_q: Query<&Ball>,
+    mut trail_q: Query<&mut DrawMode, With<Trail>>,
+    palette: Res<Palette>,
+) {
+    for ev in ev_r_hit.iter() {
+        if let Ok(ball) = ball_q.get(ev.ball_e) {
+            if let Ok(mut draw_mode) = trail_q.get_mut(ball.trail_e.unwrap()) {
+                let accent = Vec4::from(
+                    palette
+                        .get_color(&PaletteColor::PlayerAccent(ev.player_id))
+                        .as_rgba_f32(),
+                );
+                let hot = Vec4::from(palette.get_color(&PaletteColor::Ball).as_rgba_f32());
+                let col = accent.lerp(hot, ball.heat);
+                *draw_mode =
+                    DrawMode::Fill(FillMode::color(Color::rgba(col.x, col.y, col.z, col.w)));
+            }
+        }
+    }
+}
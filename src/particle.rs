@@ -0,0 +1,257 @@
+use crate::{
+    animation::TweenDoneAction,
+    ball::BallHitEvt,
+    palette::{Palette, PaletteColor},
+    player::{Player, PlayerDash, PlayerSwing},
+    player_action::PlayerActionStatus,
+    render::{BALL_Z, SHADOW_Z},
+};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_tweening::{
+    lens::{SpriteColorLens, TransformPositionLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use rand::Rng;
+use std::time::Duration;
+
+const MAX_PARTICLES_PER_BURST: usize = 12;
+const MAX_PARTICLES: usize = 64;
+// dash distance that spawns a full size dust puff
+const DUST_REF_DASH_DISTANCE: f32 = 190.;
+
+pub struct ParticlePlugin;
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<EffectSettings>()
+            .add_system(spawn_hit_burst)
+            .add_system(spawn_dash_dust)
+            .add_system(spawn_swing_ready_pulse);
+    }
+}
+
+// purely cosmetic effects
+pub struct EffectSettings {
+    pub hit_bursts: bool,
+    pub dash_dust: bool,
+    pub swing_ready_pulse: bool,
+    // the hitter's swing range ring flashes on a hit to show whose shot it was
+    pub hit_ring_glow: bool,
+}
+
+impl Default for EffectSettings {
+    fn default() -> Self {
+        Self {
+            hit_bursts: true,
+            dash_dust: true,
+            swing_ready_pulse: true,
+            hit_ring_glow: true,
+        }
+    }
+}
+
+#[derive(Component)]
+struct Particle;
+
+fn spawn_hit_burst(
+    mut commands: Commands,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    ball_q: Query<&GlobalTransform>,
+    particle_q: Query<(), With<Particle>>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    settings: Res<EffectSettings>,
+) {
+    let mut particle_count = particle_q.iter().count();
+    // cosmetic only & deliberately not GameRng - toggling the effects mustn't change a seeded match
+    let mut rng = rand::thread_rng();
+
+    for ev in ev_r_hit.iter() {
+        if !settings.hit_bursts {
+            continue;
+        }
+
+        let pos = match ball_q.get(ev.ball_e) {
+            Ok(t) => t.translation.truncate(),
+            Err(_) => continue,
+        };
+        let strength = ev.strength.clamp(0., 1.);
+        let count = ((4. + strength * 8.) as usize)
+            .min(MAX_PARTICLES_PER_BURST)
+            .min(MAX_PARTICLES.saturating_sub(particle_count));
+        particle_count += count;
+
+        // weak hits get the trail color, strong/power shots the ball color
+        let col = if ev.power_shot || strength >= 0.75 {
+            palette.get_color(&PaletteColor::Ball)
+        } else {
+            palette.get_color(&PaletteColor::BallTrail)
+        };
+        let size = 6. + 8. * strength;
+
+        for _ in 0..count {
+            let dir =
+                Vec2::new(rng.gen_range(-1. ..=1.), rng.gen_range(-1. ..=1.)).normalize_or_zero();
+            let dist = rng.gen_range(30. ..=60.) * (0.5 + strength);
+            let duration = Duration::from_millis(rng.gen_range(200..=350));
+            let start = pos.extend(BALL_Z + 0.5);
+
+            commands
+                .spawn_bundle(SpriteBundle {
+                    texture: asset_server.load("art-ish/ball.png"),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::ONE * size),
+                        color: col,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(start),
+                    ..Default::default()
+                })
+                .insert(Animator::new(Tween::new(
+                    EaseFunction::QuadraticOut,
+                    TweeningType::Once,
+                    duration,
+                    TransformPositionLens {
+                        start,
+                        end: start + (dir * dist).extend(0.),
+                    },
+                )))
+                .insert(Animator::new(
+                    Tween::new(
+                        EaseFunction::QuadraticIn,
+                        TweeningType::Once,
+                        duration,
+                        SpriteColorLens {
+                            start: col,
+                            end: Color::NONE,
+                        },
+                    )
+                    .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+                ))
+                .insert(Particle)
+                .insert(Name::new("HitParticle"));
+        }
+    }
+}
+
+// there's no jumping, so the dust is kicked up when a dash ends
+fn spawn_dash_dust(
+    mut commands: Commands,
+    dash_q: Query<(Entity, &PlayerDash, &GlobalTransform)>,
+    mut dashing: Local<HashSet<Entity>>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    settings: Res<EffectSettings>,
+) {
+    for (e, dash, t) in dash_q.iter() {
+        if let PlayerActionStatus::Active(..) = dash.status {
+            dashing.insert(e);
+            continue;
+        }
+
+        if !dashing.remove(&e) || !settings.dash_dust {
+            continue;
+        }
+
+        let size = 50. * (dash.get_distance() / DUST_REF_DASH_DISTANCE).clamp(0.5, 1.5);
+        let col = palette.get_color(&PaletteColor::Shadow);
+        let end_scale = Vec3::new(1.4, 1.2, 1.);
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load("art-ish/ball.png"),
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(1.0, 0.5) * size),
+                    color: col,
+                    ..Default::default()
+                },
+                transform: Transform {
+                    // feet
+                    translation: (t.translation.truncate() + Vec2::new(-5., -30.))
+                        .extend(SHADOW_Z + 0.1),
+                    scale: Vec2::splat(0.3).extend(1.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Animator::new(Tween::new(
+                EaseFunction::QuadraticOut,
+                TweeningType::Once,
+                Duration::from_millis(350),
+                TransformScaleLens {
+                    start: Vec2::splat(0.3).extend(1.),
+                    end: end_scale,
+                },
+            )))
+            .insert(Animator::new(
+                Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(400),
+                    SpriteColorLens {
+                        start: col,
+                        end: Color::NONE,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+            ))
+            .insert(Name::new("DashDust"));
+    }
+}
+
+// subtle ring pop once the swing is off cooldown
+fn spawn_swing_ready_pulse(
+    mut commands: Commands,
+    swing_q: Query<(Entity, &Player, &PlayerSwing)>,
+    mut cooling_down: Local<HashSet<Entity>>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    settings: Res<EffectSettings>,
+) {
+    for (e, player, swing) in swing_q.iter() {
+        if let PlayerActionStatus::Cooldown = swing.status {
+            cooling_down.insert(e);
+            continue;
+        }
+
+        if !cooling_down.remove(&e) || !settings.swing_ready_pulse {
+            continue;
+        }
+
+        let mut col = palette.get_color(&PaletteColor::PlayerAccent(player.id));
+        col.set_a(0.6);
+
+        commands.entity(e).with_children(|b| {
+            b.spawn_bundle(SpriteBundle {
+                texture: asset_server.load("art-ish/player_circle.png"),
+                sprite: Sprite {
+                    color: col,
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., 0., -0.05),
+                ..Default::default()
+            })
+            .insert(Animator::new(Tween::new(
+                EaseFunction::QuadraticOut,
+                TweeningType::Once,
+                Duration::from_millis(250),
+                TransformScaleLens {
+                    start: Vec3::ONE,
+                    end: Vec2::splat(1.2).extend(1.),
+                },
+            )))
+            .insert(Animator::new(
+                Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(250),
+                    SpriteColorLens {
+                        start: col,
+                        end: Color::NONE,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+            ))
+            .insert(Name::new("SwingReadyPulse"));
+        });
+    }
+}
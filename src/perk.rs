@@ -0,0 +1,367 @@
+use crate::{
+    ai_player_controller::AiPlayer,
+    input_binding::{InputAction, InputAxis, PlayerInput},
+    match_config::{MatchConfig, PerkPicker},
+    palette::PaletteColor,
+    player::Player,
+    rng::GameRng,
+    score::GameWonEvt,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+use rand::seq::SliceRandom;
+use std::collections::VecDeque;
+
+const GAME_WON_LABEL: &str = "perk_game_won";
+
+pub struct PerkPlugin;
+impl Plugin for PerkPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<PendingPerkPicks>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(on_game_won.label(GAME_WON_LABEL))
+                    .with_system(open_selection.after(GAME_WON_LABEL)),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Game).with_system(clear_pending_picks))
+            .add_system_set(SystemSet::on_enter(GameState::PerkSelection).with_system(setup_ui))
+            .add_system_set(
+                SystemSet::on_update(GameState::PerkSelection)
+                    .with_system(handle_selection_input)
+                    .with_system(update_ui),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::PerkSelection).with_system(cleanup_ui));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Inspectable)]
+pub enum Perk {
+    SwingRange,
+    FastSwing,
+    FastDash,
+    WideAim,
+    QuickAim,
+    Springy,
+}
+
+impl Perk {
+    pub const ALL: [Perk; 6] = [
+        Perk::SwingRange,
+        Perk::FastSwing,
+        Perk::FastDash,
+        Perk::WideAim,
+        Perk::QuickAim,
+        Perk::Springy,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Perk::SwingRange => "Swing range",
+            Perk::FastSwing => "Fast swing",
+            Perk::FastDash => "Fast dash",
+            Perk::WideAim => "Wide aim",
+            Perk::QuickAim => "Quick aim",
+            Perk::Springy => "Springy",
+        }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct Perks(pub Vec<Perk>);
+
+impl Perks {
+    pub fn count(&self, perk: Perk) -> usize {
+        self.0.iter().filter(|p| **p == perk).count()
+    }
+
+    pub fn get_swing_range_mult(&self) -> f32 {
+        1. + 0.15 * self.count(Perk::SwingRange) as f32
+    }
+
+    pub fn get_swing_cooldown_mult(&self) -> f32 {
+        0.8f32.powi(self.count(Perk::FastSwing) as i32)
+    }
+
+    pub fn get_dash_cooldown_mult(&self) -> f32 {
+        0.8f32.powi(self.count(Perk::FastDash) as i32)
+    }
+
+    pub fn get_aim_speed_mult(&self) -> f32 {
+        1. + 0.25 * self.count(Perk::QuickAim) as f32
+    }
+
+    // the apex grows w/ the square of the velocity
+    pub fn get_jump_velocity_mult(&self) -> f32 {
+        1. + 0.15 * self.count(Perk::Springy) as f32
+    }
+
+    pub fn get_aim_clamp_y(&self, base_clamp_y: f32) -> f32 {
+        (base_clamp_y + 0.1 * self.count(Perk::WideAim) as f32).min(1.)
+    }
+}
+
+pub struct PerkSelection {
+    pub player_id: usize,
+    pub options: [Perk; 3],
+    pub selected: usize,
+    axis_released: bool,
+    // the selection waits for any other queued transition (e.g. a hawk-eye review)
+    opened: bool,
+}
+
+// player ids waiting for their pick - one selection at a time, so no won game gets skipped
+#[derive(Default)]
+pub struct PendingPerkPicks(VecDeque<usize>);
+
+#[derive(Component)]
+struct PerkSelectionText;
+
+fn on_game_won(
+    mut ev_r_game_won: EventReader<GameWonEvt>,
+    mut pending: ResMut<PendingPerkPicks>,
+    config: Res<MatchConfig>,
+) {
+    for ev in ev_r_game_won.iter() {
+        let player_id = match config.perk_picker {
+            PerkPicker::Disabled => continue,
+            PerkPicker::Winner => ev.winner_id,
+            PerkPicker::Loser => ev.loser_id,
+        };
+
+        pending.0.push_back(player_id);
+    }
+}
+
+fn open_selection(
+    mut commands: Commands,
+    selection: Option<ResMut<PerkSelection>>,
+    mut pending: ResMut<PendingPerkPicks>,
+    mut state: ResMut<State<GameState>>,
+    mut rng: ResMut<GameRng>,
+) {
+    if let Some(mut selection) = selection {
+        if selection.opened {
+            return;
+        }
+
+        match state.push(GameState::PerkSelection) {
+            Ok(_) => selection.opened = true,
+            // retried next frame
+            Err(e) => debug!("Perk selection postponed: {:?}", e),
+        }
+    } else if let Some(player_id) = pending.0.pop_front() {
+        // the previous selection is gone once its state exits
+        let mut perks = Perk::ALL;
+        perks.shuffle(&mut rng.0);
+
+        commands.insert_resource(PerkSelection {
+            player_id,
+            options: [perks[0], perks[1], perks[2]],
+            selected: 1,
+            axis_released: false,
+            opened: false,
+        });
+    }
+}
+
+fn clear_pending_picks(mut pending: ResMut<PendingPerkPicks>) {
+    pending.0.clear();
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                margin: Rect::all(Val::Auto),
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(PerkSelectionText)
+        .insert(Name::new("PerkSelectionText"));
+}
+
+fn handle_selection_input(
+    mut input: ResMut<PlayerInput>,
+    selection: Option<ResMut<PerkSelection>>,
+    mut state: ResMut<State<GameState>>,
+    mut player_q: Query<(&Player, &mut Perks, Option<&AiPlayer>)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let mut selection = match selection {
+        Some(selection) => selection,
+        None => return,
+    };
+    let id = selection.player_id;
+    let mut picked = None;
+
+    if let Some((_, mut perks, ai)) = player_q.iter_mut().find(|(p, ..)| p.id == id) {
+        if ai.is_some() {
+            picked = Some(*selection.options.choose(&mut rng.0).unwrap());
+        } else {
+            let x = input
+                .get_xy_axes_raw(id, &InputAxis::MoveX, &InputAxis::MoveY)
+                .x;
+
+            if x.abs() < 0.5 {
+                selection.axis_released = true;
+            } else if selection.axis_released {
+                selection.axis_released = false;
+                selection.selected = if x < 0. {
+                    selection.selected.saturating_sub(1)
+                } else {
+                    (selection.selected + 1).min(selection.options.len() - 1)
+                };
+            }
+
+            if input.just_pressed(id, InputAction::Swing) {
+                input.use_button_action(id, InputAction::Swing);
+                picked = Some(selection.options[selection.selected]);
+            }
+        }
+
+        if let Some(perk) = picked {
+            // only apply the perk once the selection can be closed, so it's not picked twice
+            match state.pop() {
+                Ok(_) => {
+                    debug!("Player {} picked perk {:?}", id, perk);
+                    perks.0.push(perk);
+                }
+                Err(e) => warn!("Failed to close the perk selection: {:?}", e),
+            }
+        }
+    } else if let Err(e) = state.pop() {
+        // nobody to pick the perk
+        warn!("Failed to close the perk selection: {:?}", e);
+    }
+}
+
+fn update_ui(
+    selection: Option<Res<PerkSelection>>,
+    mut text_q: Query<&mut Text, With<PerkSelectionText>>,
+) {
+    let selection = match selection {
+        Some(selection) => selection,
+        None => return,
+    };
+    let options: Vec<String> = selection
+        .options
+        .iter()
+        .enumerate()
+        .map(|(i, perk)| {
+            if i == selection.selected {
+                format!("[{}]", perk.get_name())
+            } else {
+                perk.get_name().to_string()
+            }
+        })
+        .collect();
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = format!(
+            "Player {} - pick a perk\n{}",
+            selection.player_id,
+            options.join("   ")
+        );
+    }
+}
+
+fn cleanup_ui(mut commands: Commands, text_q: Query<Entity, With<PerkSelectionText>>) {
+    for e in text_q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+
+    commands.remove_resource::<PerkSelection>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::Events;
+
+    #[test]
+    fn selection_waits_for_queued_transition() {
+        let mut app = App::new();
+        app.insert_resource(State::new(GameState::Game))
+            .insert_resource(PerkSelection {
+                player_id: 1,
+                options: [Perk::SwingRange, Perk::FastSwing, Perk::FastDash],
+                selected: 1,
+                axis_released: false,
+                opened: false,
+            })
+            .add_system(open_selection);
+        // e.g. a hawk-eye review on the same frame
+        app.world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .push(GameState::HawkEye)
+            .unwrap();
+        app.update();
+
+        assert!(!app.world.get_resource::<PerkSelection>().unwrap().opened);
+    }
+
+    #[test]
+    fn games_won_during_a_selection_are_queued() {
+        let mut app = App::new();
+        app.add_event::<GameWonEvt>()
+            .insert_resource(State::new(GameState::Game))
+            .insert_resource(MatchConfig {
+                perk_picker: PerkPicker::Winner,
+                ..Default::default()
+            })
+            .init_resource::<PendingPerkPicks>()
+            .init_resource::<GameRng>()
+            .add_system(on_game_won.label(GAME_WON_LABEL))
+            .add_system(open_selection.after(GAME_WON_LABEL));
+
+        let mut ev_game_won = app.world.get_resource_mut::<Events<GameWonEvt>>().unwrap();
+        for (winner_id, loser_id) in [(1, 2), (2, 1)] {
+            ev_game_won.send(GameWonEvt {
+                winner_id,
+                loser_id,
+            });
+        }
+        app.update();
+
+        assert_eq!(
+            app.world.get_resource::<PerkSelection>().unwrap().player_id,
+            1
+        );
+        assert_eq!(
+            app.world.get_resource::<PendingPerkPicks>().unwrap().0,
+            VecDeque::from([2])
+        );
+
+        // the first pick is done
+        app.world.remove_resource::<PerkSelection>();
+        app.update();
+
+        assert_eq!(
+            app.world.get_resource::<PerkSelection>().unwrap().player_id,
+            2
+        );
+        assert!(app
+            .world
+            .get_resource::<PendingPerkPicks>()
+            .unwrap()
+            .0
+            .is_empty());
+    }
+}
@@ -1,16 +1,32 @@
 use crate::{
     ai_player_controller::AiPlayer,
     animation::{inverse_lerp, TransformRotation, TweenDoneAction},
-    ball::{spawn_ball, Ball, BallBouncedEvt, BallStatus},
+    ball::{AceEvt, Ball, BallBouncedEvt, BallStatus, DeadBallSettings, PendingServe},
+    clutch::ClutchBuff,
+    combo::Combo,
+    crowd::CrowdIntensity,
+    ends::Ends,
     extra::TransformBundle,
+    game_speed::GameTime,
     impl_player_action_timer,
-    level::{CourtRegion, CourtSettings, InitialRegion, Net, NetOffset, ServingRegion},
+    input_binding::AimSnapSettings,
+    level::{CourtRegion, CourtSettings, InitialRegion, NetOffset, ServingRegion},
+    match_config::{GameMode, MatchConfig},
     palette::PaletteColor,
+    perk::Perks,
     physics::PhysLayer,
     player_action::{ActionTimer, PlayerActionStatus},
     player_animation::{AgentAnimationData, PlayerAnimation},
+    practice::ServePractice,
+    rally::RallyCounter,
     render::{PLAYER_Z, SHADOW_Z},
-    score::{add_point_to_score, PlayerScore, Score},
+    rng::GameRng,
+    rumble::{RumbleEvt, RumbleKind},
+    score::{
+        add_point_to_score, is_golden_point, should_swap_serve, GameOverEvt, GameWonEvt,
+        GoldenPointEvt, PlayerScore, PointOutcome, Score,
+    },
+    serve::ServeChangedEvt,
     trail::FadeOutTrail,
     GameSetupPhase, GameState, WIN_HEIGHT, WIN_WIDTH,
 };
@@ -19,10 +35,10 @@ use bevy::{
     math::Vec2,
     prelude::*,
     sprite::{collide_aabb::collide, Sprite, SpriteBundle},
+    utils::HashMap,
 };
 use bevy_extensions::Vec2Conversion;
 use bevy_inspector_egui::Inspectable;
-use bevy_time::{ScaledTime, ScaledTimeDelta};
 use bevy_tweening::lens::TransformScaleLens;
 use bevy_tweening::*;
 use heron::*;
@@ -30,7 +46,26 @@ use interpolation::EaseFunction;
 use std::time::Duration;
 
 pub const AIM_RING_ROTATION_DEG: f32 = 50.;
-// todo: get rid of this by fixing the animation system order and sue an enum label for that
+pub const AIM_CLAMP_Y: f32 = 0.8;
+const AIM_ROTATION_SPEED_DEG: f32 = 260.;
+pub const SWING_RADIUS: f32 = 100.;
+// ball height a swing reaches w/o the jump
+const SWING_REACH_HEIGHT: f32 = 35.;
+// every swing hops the player, a full charge jumps the highest
+const PLAYER_JUMP_VEL_BASE: f32 = 300.;
+// even a tap hops this high
+const PLAYER_JUMP_HEIGHT_MIN: f32 = 15.;
+const PLAYER_GRAVITY: f32 = -2000.;
+const SWING_COOLDOWN_SEC: f32 = 0.35;
+const DASH_COOLDOWN_SEC: f32 = 0.5;
+// share of the aim angle applied to the face
+const FACE_ROTATION_DAMPING: f32 = 0.25;
+// higher is snappier
+const FACE_ROTATION_RESPONSIVENESS: f32 = 12.;
+const PLAYER_SPEED: f32 = 550.;
+// square collision size used to keep the player in their half
+const PLAYER_SIZE: f32 = 80.;
+// todo: get rid of this by fixing the animation system order and use an enum label for that
 pub const SWING_LABEL: &str = "swing";
 
 pub struct PlayerPlugin;
@@ -44,8 +79,17 @@ impl Plugin for PlayerPlugin {
                 .with_system(move_player.before(SWING_LABEL))
                 .with_system(aim)
                 .with_system(swing)
+                .with_system(consume_buffered_swing.before(SWING_LABEL))
+                .with_system(jump.after(SWING_LABEL))
                 .with_system(on_ball_bounced),
-        );
+        )
+        .add_system(apply_perks)
+        .init_resource::<AimAssist>()
+        .init_resource::<PlayerHandedness>()
+        .init_resource::<SwingBufferSettings>()
+        .init_resource::<SwingConfig>()
+        .init_resource::<AutoReturnSettings>()
+        .init_resource::<MoveAnimationSettings>();
     }
 }
 
@@ -54,12 +98,19 @@ pub struct Player {
     pub id: usize,
     pub aim_e: Entity,
     pub aim_charge_e: Entity,
-    side: f32,
+    pub handedness: Handedness,
+    // the side the player is on now, see Ends
+    pub left_side: bool,
 }
 
 impl Player {
     pub fn is_left(&self) -> bool {
-        is_left_player_id(self.id)
+        self.left_side
+    }
+
+    // which way the player's sprites face - towards the net unless mirrored by handedness
+    pub fn is_facing_right(&self) -> bool {
+        is_facing_right(self.is_left(), self.handedness)
     }
 
     pub fn get_sign(&self) -> f32 {
@@ -71,19 +122,47 @@ impl Player {
     }
 }
 
-// todo: just add a side enum and add it to player or as a component? (covered by the size field - currently quite a mess)
+fn is_facing_right(is_left: bool, handedness: Handedness) -> bool {
+    is_left != (handedness == Handedness::Left)
+}
+
+// purely cosmetic
+#[derive(Default, Clone, Copy, PartialEq, Debug, Inspectable)]
+pub enum Handedness {
+    #[default]
+    Right,
+    Left,
+}
+
+// keyed by player id, so doubles partners don't share it - right-handed unless set
+#[derive(Default)]
+pub struct PlayerHandedness(HashMap<usize, Handedness>);
+
+impl PlayerHandedness {
+    pub fn get(&self, player_id: usize) -> Handedness {
+        self.0.get(&player_id).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, player_id: usize, handedness: Handedness) {
+        self.0.insert(player_id, handedness);
+    }
+}
+
+// todo: just add a side enum and add it to player or as a component?
+// odd ids play on the left, so doubles teams are 1 & 3 vs 2 & 4
 pub fn is_left_player_id(id: usize) -> bool {
-    id == 1
+    id % 2 == 1
 }
 
 #[derive(Default, Component, Inspectable)]
 pub struct PlayerMovement {
     speed: f32,
-    charging_speed: f32,
     easing_time: f32,
     time_to_max_speed: f32,
     pub raw_dir: Vec2,
     last_non_zero_raw_dir: Vec2,
+    // how long the player has been slow enough to go idle
+    idle_debounce_sec: f32,
 }
 
 #[derive(Default, Component, Inspectable)]
@@ -96,24 +175,121 @@ pub struct PlayerDash {
     speed: f32,
 }
 
+impl PlayerDash {
+    pub fn get_distance(&self) -> f32 {
+        self.speed * self.duration_sec
+    }
+}
+
 impl_player_action_timer!(PlayerDash, Vec2);
 
+// per-player, the springy perk scales the velocity
+#[derive(Component, Inspectable)]
+pub struct JumpStats {
+    pub velocity: f32,
+    pub min_height: f32,
+    pub gravity: f32,
+    height: f32,
+    current_velocity: f32,
+    // of the current/last jump
+    apex: f32,
+}
+
+impl Default for JumpStats {
+    fn default() -> Self {
+        Self {
+            velocity: PLAYER_JUMP_VEL_BASE,
+            min_height: PLAYER_JUMP_HEIGHT_MIN,
+            gravity: PLAYER_GRAVITY,
+            height: 0.,
+            current_velocity: 0.,
+            apex: 0.,
+        }
+    }
+}
+
+impl JumpStats {
+    // peak height of a swing jump of the given strength
+    pub fn get_apex(&self, strength: f32) -> f32 {
+        let vel = self.velocity * strength.max(0.);
+        (vel * vel / (2. * self.gravity.abs().max(f32::EPSILON))).max(self.min_height)
+    }
+
+    // highest ball a swing of the given strength can hit
+    pub fn get_reach(&self, strength: f32) -> f32 {
+        SWING_REACH_HEIGHT + self.get_apex(strength)
+    }
+
+    // current/last jump apex relative to a full charge jump w/ the base stats
+    pub fn get_apex_ratio(&self) -> f32 {
+        self.apex / JumpStats::default().get_apex(1.)
+    }
+
+    fn is_airborne(&self) -> bool {
+        self.height > 0. || self.current_velocity > 0.
+    }
+
+    // the swing reports Active for its whole window, so only the first call starts a jump
+    fn start(&mut self, strength: f32) {
+        if self.is_airborne() {
+            return;
+        }
+
+        self.apex = self.get_apex(strength);
+        self.current_velocity = (2. * self.gravity.abs() * self.apex).sqrt();
+    }
+
+    fn step(&mut self, dt: f32) {
+        if self.height <= 0. && self.current_velocity <= 0. {
+            return;
+        }
+
+        self.current_velocity += self.gravity * dt;
+        self.height = (self.height + self.current_velocity * dt).max(0.);
+
+        if self.height <= 0. {
+            self.current_velocity = 0.;
+        }
+    }
+}
+
 #[derive(Default, Component, Inspectable)]
 pub struct PlayerAim {
     pub raw_dir: Vec2,
     pub dir: Vec2,
+    // max turn rate at full stick deflection
+    pub rotation_speed_rad: f32,
 }
 
 #[derive(Component, Inspectable)]
 pub struct SwingRangeSprite;
 
+pub struct AimAssist {
+    pub enabled: bool,
+    pub strength: f32,
+}
+
+impl Default for AimAssist {
+    fn default() -> Self {
+        Self {
+            // opt-in from the menu
+            enabled: false,
+            strength: 0.35,
+        }
+    }
+}
+
 #[derive(Default, Component, Inspectable)]
 pub struct PlayerSwing {
     pub status: PlayerActionStatus<f32>,
     pub duration_sec: f32,
     pub cooldown_sec: f32,
+    pub radius: f32,
     #[inspectable(ignore)]
     pub timer: Timer,
+    // swing released during cooldown - (strength, buffer window)
+    #[inspectable(ignore)]
+    pub buffered: Option<(f32, Timer)>,
 }
 
 impl PlayerSwing {
@@ -125,6 +301,165 @@ impl PlayerSwing {
 
 impl_player_action_timer!(PlayerSwing, f32);
 
+// base values, perks scale these
+pub struct SwingConfig {
+    pub duration_sec: f32,
+    pub cooldown_sec: f32,
+    // releasing sooner counts as a tap
+    pub min_charge_sec: f32,
+    pub tap: SwingTap,
+    pub hitbox: SwingHitbox,
+    // dashing cancels a charging/active swing, the cooldown still applies
+    pub dash_cancels_swing: bool,
+    // share of the movement speed kept while charging, 1 is no penalty
+    pub charging_speed_ratio: f32,
+}
+
+impl Default for SwingConfig {
+    fn default() -> Self {
+        Self {
+            duration_sec: 0.35,
+            cooldown_sec: SWING_COOLDOWN_SEC,
+            min_charge_sec: 0.,
+            tap: SwingTap::default(),
+            hitbox: SwingHitbox::default(),
+            dash_cancels_swing: false,
+            charging_speed_ratio: 125. / PLAYER_SPEED,
+        }
+    }
+}
+
+// the swing collider is always a circle, this narrows down what counts as a hit
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum SwingHitbox {
+    #[default]
+    Circle,
+    // forward biased - balls behind the player (relative to the aim) only count within this share of the radius
+    Forward(f32),
+}
+
+impl SwingHitbox {
+    pub fn is_in_reach(&self, to_ball: Vec2, aim_dir: Vec2, radius: f32) -> bool {
+        match self {
+            SwingHitbox::Circle => true,
+            SwingHitbox::Forward(back_reach) => {
+                to_ball.dot(aim_dir.normalize_or_zero()) >= -radius * back_reach
+            }
+        }
+    }
+}
+
+// what releasing the swing below SwingConfig::min_charge_sec does
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum SwingTap {
+    // the swing stays ready
+    Ignore,
+    // weakest possible shot
+    #[default]
+    Block,
+}
+
+// px/s
+pub struct MoveAnimationSettings {
+    pub idle_speed: f32,
+    // walking below this, charging always walks
+    pub run_speed: f32,
+    // share of the threshold the current state gets to keep it from flickering
+    pub hysteresis: f32,
+    // has to stay below the idle speed this long to go idle
+    pub idle_debounce_sec: f32,
+}
+
+impl Default for MoveAnimationSettings {
+    fn default() -> Self {
+        Self {
+            idle_speed: 6.,
+            run_speed: 250.,
+            hysteresis: 0.15,
+            idle_debounce_sec: 0.08,
+        }
+    }
+}
+
+impl MoveAnimationSettings {
+    pub fn get_animation(
+        &self,
+        current: &PlayerAnimation,
+        speed: f32,
+        charging: bool,
+    ) -> PlayerAnimation {
+        let exceeds = |threshold: f32, is_current: bool| {
+            if is_current {
+                speed > threshold * (1. - self.hysteresis)
+            } else {
+                speed > threshold * (1. + self.hysteresis)
+            }
+        };
+
+        if !exceeds(self.idle_speed, *current != PlayerAnimation::Idle) {
+            PlayerAnimation::Idle
+        } else if !charging && exceeds(self.run_speed, *current == PlayerAnimation::Running) {
+            PlayerAnimation::Running
+        } else {
+            PlayerAnimation::Walking
+        }
+    }
+
+    // None keeps the current animation - going idle has to last idle_debounce_sec
+    pub fn get_debounced_animation(
+        &self,
+        current: &PlayerAnimation,
+        speed: f32,
+        charging: bool,
+        idle_sec: &mut f32,
+        dt: f32,
+    ) -> Option<PlayerAnimation> {
+        let animation = self.get_animation(current, speed, charging);
+
+        if animation == PlayerAnimation::Idle && *current != PlayerAnimation::Idle {
+            *idle_sec += dt;
+        } else {
+            *idle_sec = 0.;
+        }
+
+        let debounced = animation == PlayerAnimation::Idle && *idle_sec < self.idle_debounce_sec;
+
+        if animation != *current && !debounced {
+            Some(animation)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct AutoReturnSettings {
+    pub enabled: bool,
+    // slower than walking so it doesn't feel like the player lost control
+    pub speed: f32,
+    // home is at the middle of the baseline, offset into the court
+    pub baseline_offset: f32,
+}
+
+impl Default for AutoReturnSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            speed: 80.,
+            baseline_offset: 80.,
+        }
+    }
+}
+
+pub struct SwingBufferSettings {
+    pub buffer_sec: f32,
+}
+
+impl Default for SwingBufferSettings {
+    fn default() -> Self {
+        Self { buffer_sec: 0.15 }
+    }
+}
+
 #[derive(Bundle)]
 pub struct PlayerBundle {
     player: Player,
@@ -132,49 +467,78 @@ pub struct PlayerBundle {
     dash: PlayerDash,
     swing: PlayerSwing,
     score: PlayerScore,
+    combo: Combo,
+    perks: Perks,
+    jump: JumpStats,
 }
 
 // todo: just remove the bundle and insert the components directly?
 impl PlayerBundle {
-    fn new(id: usize, initial_dir: Vec2, aim_e: Entity, aim_charge_e: Entity) -> Self {
+    fn new(
+        id: usize,
+        aim_e: Entity,
+        aim_charge_e: Entity,
+        handedness: Handedness,
+        is_left: bool,
+        swing_config: &SwingConfig,
+    ) -> Self {
         Self {
             player: Player {
                 id,
-                side: -initial_dir.x.signum(),
                 aim_e,
                 aim_charge_e,
+                handedness,
+                left_side: is_left,
             },
             movement: PlayerMovement {
-                speed: 550.,
-                charging_speed: 125.,
+                speed: PLAYER_SPEED,
                 time_to_max_speed: 0.11,
                 ..Default::default()
             },
             dash: PlayerDash {
                 speed: 2200.,
                 duration_sec: 0.085,
-                cooldown_sec: 0.5,
+                cooldown_sec: DASH_COOLDOWN_SEC,
                 ..Default::default()
             },
             swing: PlayerSwing {
-                duration_sec: 0.35,
-                cooldown_sec: 0.35,
+                duration_sec: swing_config.duration_sec,
+                cooldown_sec: swing_config.cooldown_sec,
+                radius: SWING_RADIUS,
                 ..Default::default()
             },
             score: PlayerScore {
                 ..Default::default()
             },
+            combo: Combo::default(),
+            perks: Perks::default(),
+            jump: JumpStats::default(),
         }
     }
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>, region: Res<InitialRegion>) {
-    if cfg!(feature = "debug") {
-        spawn_player(1, &mut commands, &asset_server, &region);
-    } else {
-        for id in 1..=2 {
-            spawn_player(id, &mut commands, &asset_server, &region);
-        }
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    region: Res<InitialRegion>,
+    handedness: Res<PlayerHandedness>,
+    swing_config: Res<SwingConfig>,
+    config: Res<MatchConfig>,
+    game_mode: Res<GameMode>,
+    ends: Res<Ends>,
+) {
+    // AI players are spawned by the AI controller
+    for id in (1..=config.get_player_count()).filter(|id| !game_mode.is_ai_player(*id)) {
+        spawn_player(
+            id,
+            &mut commands,
+            &asset_server,
+            &region,
+            &handedness,
+            &swing_config,
+            &config,
+            &ends,
+        );
     }
 }
 
@@ -183,27 +547,42 @@ pub fn spawn_player<'a, 'b, 'c>(
     commands: &'c mut Commands<'a, 'b>,
     asset_server: &Res<AssetServer>,
     region: &Res<InitialRegion>,
+    handedness: &Res<PlayerHandedness>,
+    swing_config: &Res<SwingConfig>,
+    config: &Res<MatchConfig>,
+    ends: &Res<Ends>,
 ) -> EntityCommands<'a, 'b, 'c> {
+    let is_left = ends.is_left(id);
     let x = WIN_WIDTH / 4.;
-    let x = if id == 1 { -x } else { x };
-    let is_left = x < 0.;
-    let mut player_y = 150.;
+    let x = if is_left { -x } else { x };
+    // keep the whole player inside the play area
+    let mut player_y = config
+        .player_spawn_y
+        .abs()
+        .min(WIN_HEIGHT / 2. - PLAYER_SIZE / 2.);
     let is_serving = region.0.is_left() == is_left;
     if (is_serving && region.0.is_bottom()) || (!is_serving && region.0.is_top()) {
         player_y *= -1.;
     }
+    // doubles partners take the other half of their side
+    if id > 2 {
+        player_y *= -1.;
+    }
 
     let initial_dir = if is_left { Vec2::X } else { -Vec2::X };
+    let handedness = handedness.get(id);
+    let facing_right = is_facing_right(is_left, handedness);
 
     let mut body_e = None;
     let mut body_root_e = None;
+    let mut jump_root_e = None;
 
     // face
     let face_e = commands
         .spawn_bundle(SpriteBundle {
             texture: asset_server.load("art-ish/face_happy.png"),
             sprite: Sprite {
-                flip_x: !is_left,
+                flip_x: !facing_right,
                 ..Default::default()
             },
             ..Default::default()
@@ -225,6 +604,7 @@ pub fn spawn_player<'a, 'b, 'c>(
         .insert(PlayerAim {
             dir: initial_dir,
             raw_dir: Vec2::ZERO,
+            rotation_speed_rad: AIM_ROTATION_SPEED_DEG.to_radians(),
         })
         .with_children(|b| {
             // aim arrow
@@ -250,17 +630,20 @@ pub fn spawn_player<'a, 'b, 'c>(
         .insert(PaletteColor::PlayerCharge)
         .id();
 
+    let bundle = PlayerBundle::new(id, aim_e, aim_charge_e, handedness, is_left, swing_config);
     let mut p = commands.spawn_bundle(TransformBundle::from_xyz(x, player_y, PLAYER_Z));
-    p.insert_bundle(PlayerBundle::new(id, initial_dir, aim_e, aim_charge_e))
+    p.insert_bundle(bundle)
         .insert(RigidBody::KinematicPositionBased)
-        .insert(CollisionShape::Sphere { radius: 100. })
+        .insert(CollisionShape::Sphere {
+            radius: SWING_RADIUS,
+        })
         .insert(CollisionLayers::none())
         .insert(Name::new("Player"))
         .add_child(aim_e)
         .add_child(aim_charge_e)
         .with_children(|b| {
             // circle
-            let rotation_speed = if is_left {
+            let rotation_speed = if facing_right {
                 -AIM_RING_ROTATION_DEG
             } else {
                 AIM_RING_ROTATION_DEG
@@ -270,43 +653,56 @@ pub fn spawn_player<'a, 'b, 'c>(
                 transform: Transform::from_xyz(0., 0., -0.1),
                 ..Default::default()
             })
-            .insert(PaletteColor::PlayerAim)
+            .insert(PaletteColor::PlayerAccent(id))
             .insert(SwingRangeSprite)
             .insert(TransformRotation::new(rotation_speed.to_radians()));
 
-            // body root
-            body_root_e = Some(
+            // jump root - the swing hop, so it doesn't fight the body root tweens
+            jump_root_e = Some(
                 b.spawn_bundle(TransformBundle::from_xyz(0., 0., 0.))
-                    .insert(Name::new("player_body_root"))
-                    .add_child(face_e)
+                    .insert(Name::new("player_jump_root"))
                     .with_children(|b| {
-                        // body
-                        body_e = Some(
-                            b.spawn_bundle(SpriteBundle {
-                                texture: asset_server.load("art-ish/player_body.png"),
-                                ..Default::default()
-                            })
-                            .insert(PaletteColor::Player)
-                            .insert(Animator::<Transform>::default())
-                            .insert(Name::new("player_body"))
-                            .with_children(|b| {
-                                // shadow
-                                b.spawn_bundle(SpriteBundle {
-                                    texture: asset_server.load("art-ish/player_body.png"),
-                                    transform: Transform {
-                                        scale: Vec3::new(1.0, 0.5, 1.),
-                                        translation: Vec3::new(-5., -30., -PLAYER_Z + SHADOW_Z),
-                                        ..Default::default()
-                                    },
-                                    ..Default::default()
+                        // body root
+                        body_root_e = Some(
+                            b.spawn_bundle(TransformBundle::from_xyz(0., 0., 0.))
+                                .insert(Name::new("player_body_root"))
+                                .add_child(face_e)
+                                .with_children(|b| {
+                                    // body
+                                    body_e = Some(
+                                        b.spawn_bundle(SpriteBundle {
+                                            texture: asset_server.load("art-ish/player_body.png"),
+                                            ..Default::default()
+                                        })
+                                        .insert(PaletteColor::PlayerAccent(id))
+                                        .insert(Animator::<Transform>::default())
+                                        .insert(Name::new("player_body"))
+                                        .with_children(|b| {
+                                            // shadow
+                                            b.spawn_bundle(SpriteBundle {
+                                                texture: asset_server
+                                                    .load("art-ish/player_body.png"),
+                                                transform: Transform {
+                                                    scale: Vec3::new(1.0, 0.5, 1.),
+                                                    translation: Vec3::new(
+                                                        -5.,
+                                                        -30.,
+                                                        -PLAYER_Z + SHADOW_Z,
+                                                    ),
+                                                    ..Default::default()
+                                                },
+                                                ..Default::default()
+                                            })
+                                            .insert(PaletteColor::Shadow)
+                                            .insert(Name::new("player_shadow"));
+                                        })
+                                        .id(),
+                                    );
                                 })
-                                .insert(PaletteColor::Shadow)
-                                .insert(Name::new("player_shadow"));
-                            })
-                            .id(),
+                                .insert(Animator::<Transform>::default())
+                                .id(),
                         );
                     })
-                    .insert(Animator::<Transform>::default())
                     .id(),
             );
         })
@@ -315,6 +711,7 @@ pub fn spawn_player<'a, 'b, 'c>(
             face_e,
             body_e: body_e.unwrap(),
             body_root_e: body_root_e.unwrap(),
+            jump_root_e: jump_root_e.unwrap(),
         });
     p
 }
@@ -328,20 +725,31 @@ fn move_player(
         &mut Transform,
         &PlayerSwing,
         &mut AgentAnimationData,
+        Option<&ClutchBuff>,
     )>,
-    net_q: Query<&GlobalTransform, With<Net>>,
-    time: ScaledTime,
+    time: GameTime,
     net_offset: Res<NetOffset>,
+    court_set: Res<CourtSettings>,
+    auto_return: Res<AutoReturnSettings>,
+    move_anim: Res<MoveAnimationSettings>,
+    swing_config: Res<SwingConfig>,
 ) {
-    for (player, mut player_movement, player_dash, mut player_t, player_swing, mut p_anim) in
-        query.iter_mut()
+    for (
+        player,
+        mut player_movement,
+        player_dash,
+        mut player_t,
+        player_swing,
+        mut p_anim,
+        clutch,
+    ) in query.iter_mut()
     {
         let charging = matches!(player_swing.status, PlayerActionStatus::Charging(_));
         let speed = if charging {
-            player_movement.charging_speed
+            player_movement.speed * swing_config.charging_speed_ratio.max(0.)
         } else {
             player_movement.speed
-        };
+        } * clutch.map_or(1., |c| c.speed_mult);
         let dir = if player_movement.raw_dir != Vec2::ZERO {
             player_movement.raw_dir
         } else {
@@ -380,20 +788,37 @@ fn move_player(
             player_movement.easing_time = player_movement.time_to_max_speed;
         }
 
+        // drift back home once the player has come to a stop, any input takes over right away
+        if auto_return.enabled
+            && !dashing
+            && !charging
+            && player_movement.raw_dir == Vec2::ZERO
+            && player_movement.easing_time <= 0.
+        {
+            let home_x = if player.is_left() {
+                court_set.left + auto_return.baseline_offset
+            } else {
+                court_set.right - auto_return.baseline_offset
+            };
+            let home = Vec3::new(home_x, 0., final_pos.z);
+            final_pos += (home - final_pos)
+                .clamp_length_max(auto_return.speed * time.scaled_delta_seconds());
+        }
+
         // nice2have: get/store properly
-        let player_size = Vec2::splat(80.);
+        let player_size = Vec2::splat(PLAYER_SIZE);
         let is_left = player.is_left();
         // nice2have: get (from resource or component)
         let player_area_size = if is_left {
-            Vec2::new(WIN_WIDTH / 2. + net_offset.0, WIN_HEIGHT)
+            Vec2::new(WIN_WIDTH / 2. + net_offset.current_offset, WIN_HEIGHT)
         } else {
-            Vec2::new(WIN_WIDTH / 2. - net_offset.0, WIN_HEIGHT)
+            Vec2::new(WIN_WIDTH / 2. - net_offset.current_offset, WIN_HEIGHT)
         };
         let pos_offset = Vec3::new(player_area_size.x / 2., 0., 0.);
         let player_area_pos = if is_left {
-            Vec3::X * net_offset.0 - pos_offset
+            Vec3::X * net_offset.current_offset - pos_offset
         } else {
-            Vec3::X * net_offset.0 + pos_offset
+            Vec3::X * net_offset.current_offset + pos_offset
         };
 
         // nice2have: using colliders would probably make more sense
@@ -403,16 +828,14 @@ fn move_player(
             player_movement.easing_time = 0.;
             player_movement.last_non_zero_raw_dir = Vec2::ZERO;
 
-            if let Ok(net_t) = net_q.get_single() {
-                let player_x = player_t.translation.x;
-                let player_half_w = player_size.x / 2.;
-                let net_x = net_t.translation.x;
+            let player_x = player_t.translation.x;
+            let player_half_w = player_size.x / 2.;
+            let net_x = net_offset.current_offset;
 
-                if is_left && (player_x + player_half_w) > net_x {
-                    player_t.translation.x = net_x - player_half_w;
-                } else if !is_left && (player_x - player_half_w) < net_x {
-                    player_t.translation.x = net_x + player_half_w;
-                }
+            if is_left && (player_x + player_half_w) > net_x {
+                player_t.translation.x = net_x - player_half_w;
+            } else if !is_left && (player_x - player_half_w) < net_x {
+                player_t.translation.x = net_x + player_half_w;
             }
 
             if p_anim.animation != PlayerAnimation::Idle {
@@ -421,16 +844,19 @@ fn move_player(
 
             trace!("{}: {:?}", if is_left { "LeftP" } else { "RightP" }, coll);
         } else {
-            if (final_pos - player_t.translation).length().abs() > 0.1 {
-                if !dashing {
-                    if charging && p_anim.animation != PlayerAnimation::Walking {
-                        p_anim.animation = PlayerAnimation::Walking;
-                    } else if !charging && p_anim.animation != PlayerAnimation::Running {
-                        p_anim.animation = PlayerAnimation::Running;
-                    }
+            let frame_speed = (final_pos - player_t.translation).length()
+                / time.scaled_delta_seconds().max(f32::EPSILON);
+
+            if !dashing || frame_speed == 0. {
+                if let Some(animation) = move_anim.get_debounced_animation(
+                    &p_anim.animation,
+                    frame_speed,
+                    charging,
+                    &mut player_movement.idle_debounce_sec,
+                    time.scaled_delta_seconds(),
+                ) {
+                    p_anim.animation = animation;
                 }
-            } else if p_anim.animation != PlayerAnimation::Idle {
-                p_anim.animation = PlayerAnimation::Idle;
             }
 
             player_t.translation = final_pos;
@@ -444,13 +870,16 @@ fn move_player(
 
 // todo: clamp angle based on Y distance from center?
 fn aim(
-    player_q: Query<(&Player, &AgentAnimationData)>,
+    player_q: Query<(&Player, &AgentAnimationData, &Perks, &GlobalTransform)>,
     mut aim_q: Query<(&mut PlayerAim, &mut Transform, &Parent)>,
     mut transform_q: Query<&mut Transform, Without<PlayerAim>>,
-    time: ScaledTime,
+    time: GameTime,
+    aim_assist: Res<AimAssist>,
+    aim_snap: Res<AimSnapSettings>,
+    court_set: Res<CourtSettings>,
 ) {
     for (mut aim, mut aim_t, aim_parent) in aim_q.iter_mut() {
-        if let Ok((p, p_anim)) = player_q.get(aim_parent.0) {
+        if let Ok((p, p_anim, perks, p_t)) = player_q.get(aim_parent.0) {
             let mut dir = aim.raw_dir.normalize_or_zero();
 
             if dir == Vec2::ZERO {
@@ -458,7 +887,7 @@ fn aim(
             }
 
             let clamp_x = 1.;
-            let clamp_y = 0.8;
+            let clamp_y = perks.get_aim_clamp_y(AIM_CLAMP_Y);
             let player_x_sign = p.get_sign();
 
             if dir == Vec2::new(player_x_sign, 0.) {
@@ -470,9 +899,30 @@ fn aim(
                 dir = dir.clamp(Vec2::new(-clamp_x, -clamp_y), Vec2::new(-clamp_x, clamp_y));
             }
 
+            if aim_assist.enabled {
+                // nudge barely deflected aim towards the middle of the opponent's court
+                let target = Vec2::new(-player_x_sign * court_set.right / 2., 0.);
+                let to_target = target - p_t.translation.truncate();
+                let safe_y = (to_target.y / to_target.x.abs().max(1.)).clamp(-clamp_y, clamp_y);
+                let assist_t = (1. - aim.raw_dir.length().min(1.)) * aim_assist.strength;
+                dir.y += (safe_y - dir.y) * assist_t;
+            }
+
+            if let Some(snap) = aim_snap.get_snap(p.id) {
+                let step = std::f32::consts::TAU / snap.directions as f32;
+                let angle = (dir.y.atan2(dir.x) / step).round() * step;
+                let snapped = Vec2::new(angle.cos(), angle.sin());
+
+                // keep the x = ±1 form of the clamped dir, vertical snaps are ignored
+                if snapped.x.abs() > 0.01 {
+                    dir = snapped / snapped.x.abs();
+                    dir.y = dir.y.clamp(-clamp_y, clamp_y);
+                }
+            }
+
             // nice2have: extract this to extensions & for now just move to extra
             let target_rotation = Quat::from_axis_angle(-Vec3::Z, dir.angle_between(Vec2::Y));
-            let limit = 260f32.to_radians() * time.scaled_delta_seconds() * aim.raw_dir.length();
+            let limit = aim.rotation_speed_rad * time.scaled_delta_seconds() * aim.raw_dir.length();
             if target_rotation.angle_between(aim_t.rotation) <= limit {
                 aim_t.rotation = Quat::from_axis_angle(-Vec3::Z, dir.angle_between(Vec2::Y));
             } else {
@@ -490,10 +940,46 @@ fn aim(
             aim.dir = clamped_dir.truncate();
 
             if let Ok(mut face_t) = transform_q.get_mut(p_anim.face_e) {
-                let axis = if p.is_left() { Vec2::X } else { -Vec2::X };
-                face_t.rotation =
-                    Quat::from_axis_angle(-Vec3::Z, aim.dir.angle_between(axis) * 0.25);
+                let axis = if p.is_facing_right() {
+                    Vec2::X
+                } else {
+                    -Vec2::X
+                };
+                let target_rotation = Quat::from_axis_angle(
+                    -Vec3::Z,
+                    aim.dir.angle_between(axis) * FACE_ROTATION_DAMPING,
+                );
+                // ease towards the aim so the face lags slightly behind
+                face_t.rotation = face_t.rotation.slerp(
+                    target_rotation,
+                    (FACE_ROTATION_RESPONSIVENESS * time.scaled_delta_seconds()).min(1.),
+                );
+            }
+        }
+    }
+}
+
+// fires a buffered swing once the cooldown is over, so the buffer can't skip the cooldown
+fn consume_buffered_swing(mut swing_q: Query<&mut PlayerSwing>, time: GameTime) {
+    for mut swing in swing_q.iter_mut() {
+        let ready = matches!(swing.status, PlayerActionStatus::Ready);
+        let mut fire = None;
+
+        if let Some((strength, timer)) = swing.buffered.as_mut() {
+            timer.tick(time.scaled_delta());
+
+            if ready && !timer.finished() {
+                fire = Some(*strength);
             }
+
+            if ready || timer.finished() {
+                swing.buffered = None;
+            }
+        }
+
+        if let Some(strength) = fire {
+            swing.status = PlayerActionStatus::Active(strength);
+            swing.timer = Timer::from_seconds(swing.duration_sec, false);
         }
     }
 }
@@ -504,9 +990,12 @@ fn swing(
         ChangeTrackers<PlayerSwing>,
         &mut CollisionLayers,
         &mut AgentAnimationData,
+        &mut JumpStats,
     )>,
 ) {
-    for (player_swing, player_swing_tracker, mut coll_layers, mut anim) in query.iter_mut() {
+    for (player_swing, player_swing_tracker, mut coll_layers, mut anim, mut jump) in
+        query.iter_mut()
+    {
         if player_swing_tracker.is_changed() {
             match player_swing.status {
                 PlayerActionStatus::Ready
@@ -514,8 +1003,9 @@ fn swing(
                 | PlayerActionStatus::Charging(_) => {
                     *coll_layers = CollisionLayers::none();
                 }
-                PlayerActionStatus::Active(_) => {
+                PlayerActionStatus::Active(strength) => {
                     *coll_layers = CollisionLayers::all::<PhysLayer>();
+                    jump.start(strength);
 
                     // 2fix: animation should fire only after collision or the timer runs out
                     anim.animation = PlayerAnimation::Shooting;
@@ -525,6 +1015,51 @@ fn swing(
     }
 }
 
+fn jump(
+    mut jump_q: Query<(&mut JumpStats, &AgentAnimationData)>,
+    mut transform_q: Query<&mut Transform>,
+    time: GameTime,
+) {
+    for (mut jump, anim) in jump_q.iter_mut() {
+        jump.step(time.scaled_delta_seconds());
+
+        if let Ok(mut t) = transform_q.get_mut(anim.jump_root_e) {
+            t.translation.y = jump.height;
+        }
+    }
+}
+
+fn apply_perks(
+    mut query: Query<
+        (
+            &Player,
+            &Perks,
+            &mut CollisionShape,
+            &mut PlayerSwing,
+            &mut PlayerDash,
+            &mut JumpStats,
+        ),
+        Changed<Perks>,
+    >,
+    mut aim_q: Query<&mut PlayerAim>,
+    swing_config: Res<SwingConfig>,
+) {
+    for (player, perks, mut shape, mut swing, mut dash, mut jump) in query.iter_mut() {
+        swing.radius = SWING_RADIUS * perks.get_swing_range_mult();
+        *shape = CollisionShape::Sphere {
+            radius: swing.radius,
+        };
+        swing.cooldown_sec = swing_config.cooldown_sec * perks.get_swing_cooldown_mult();
+        dash.cooldown_sec = DASH_COOLDOWN_SEC * perks.get_dash_cooldown_mult();
+        jump.velocity = PLAYER_JUMP_VEL_BASE * perks.get_jump_velocity_mult();
+
+        if let Ok(mut aim) = aim_q.get_mut(player.aim_e) {
+            aim.rotation_speed_rad =
+                AIM_ROTATION_SPEED_DEG.to_radians() * perks.get_aim_speed_mult();
+        }
+    }
+}
+
 pub fn get_swing_multiplier_clamped(duration: f32) -> f32 {
     get_swing_multiplier(duration).clamp(0.4, 1.)
 }
@@ -536,66 +1071,170 @@ pub fn get_swing_multiplier(duration: f32) -> f32 {
 fn on_ball_bounced(
     mut commands: Commands,
     mut ev_r_ball_bounced: EventReader<BallBouncedEvt>,
-    player_q: Query<&Player>,
-    mut ball_q: Query<(&Ball, &mut BallStatus, &Transform)>,
-    asset_server: Res<AssetServer>,
+    mut ev_w_golden_point: EventWriter<GoldenPointEvt>,
+    mut ev_w_rumble: EventWriter<RumbleEvt>,
+    (mut ev_w_game_won, mut ev_w_game_over, mut ev_w_ace, mut ev_w_serve_changed): (
+        EventWriter<GameWonEvt>,
+        EventWriter<GameOverEvt>,
+        EventWriter<AceEvt>,
+        EventWriter<ServeChangedEvt>,
+    ),
+    player_q: Query<(&Player, &Transform)>,
+    mut combo_q: Query<(&Player, &mut Combo)>,
+    mut ball_q: Query<(&mut Ball, &mut BallStatus, &Transform)>,
+    dead_ball: Res<DeadBallSettings>,
     mut serving_region: ResMut<ServingRegion>,
     entity_q: Query<Entity>,
     mut score: ResMut<Score>,
     court_set: Res<CourtSettings>,
+    config: Res<MatchConfig>,
+    (mut crowd, mut rng, mut practice, mut rally, ends): (
+        ResMut<CrowdIntensity>,
+        ResMut<GameRng>,
+        ResMut<ServePractice>,
+        ResMut<RallyCounter>,
+        Res<Ends>,
+    ),
 ) {
     for ev in ev_r_ball_bounced.iter() {
-        if let Ok((ball, mut status, ball_t)) = ball_q.get_mut(ev.ball_e) {
+        if let Ok((mut ball, mut status, ball_t)) = ball_q.get_mut(ev.ball_e) {
             let ball_res = match *status {
-                BallStatus::Fault(count, player_id) => {
-                    // nice2have: limit might come from an upgrade
-                    let limit = 1;
-                    let losing_player = if count > limit { Some(player_id) } else { None };
-                    let fault_count = if count > limit { 0 } else { count };
-                    Some((losing_player, fault_count, "double fault"))
+                // every serve bounce ends a practice 'point' & the same player serves again
+                BallStatus::Fault(..) if practice.enabled => {
+                    practice.faulted += 1;
+                    Some((None, 0, "practice fault"))
                 }
-                BallStatus::Rally(player_id) => {
-                    // nice2have: limit might come from an upgrade
-                    let bounce_limit = 1;
-
-                    // out of bounds
-                    if ball.region.is_out_of_bounds() && ev.bounce_count == 1 {
-                        Some((Some(player_id), 0, "shooting out of bounds"))
-                    } else if ev.bounce_count > bounce_limit {
-                        let player = player_q.iter().find(|p| p.side == ev.side).unwrap();
-
-                        Some((Some(player.id), 0, "too many bounces"))
-                    } else {
-                        None
+                BallStatus::Rally(_) | BallStatus::Let(..) if practice.enabled => {
+                    if ball.hit_count == 1 {
+                        practice.landed += 1;
                     }
+                    Some((None, 0, "practice serve"))
+                }
+                status => {
+                    // the ball might have moved on since it bounced this frame
+                    let out = court_set.is_out_of_bounds(ev.pos, config.out_margin);
+                    let player_ids: Vec<usize> = player_q.iter().map(|(p, _)| p.id).collect();
+                    get_ball_result(
+                        status,
+                        ev.bounce_count,
+                        ev.side,
+                        ball.netted,
+                        out,
+                        &player_ids,
+                        &ends,
+                        &config,
+                    )
                 }
-                BallStatus::Serve(..) | BallStatus::Used => None,
             };
 
+            // only the serve has hit the ball & the receiving side let it bounce
+            if let (BallStatus::Rally(player_id), Some((Some(losing_player), ..))) =
+                (*status, ball_res)
+            {
+                if ball.hit_count == 1
+                    && is_left_player_id(player_id) != is_left_player_id(losing_player)
+                {
+                    debug!("Player {} served an ace", player_id);
+                    ev_w_ace.send(AceEvt { player_id });
+                }
+            }
+
             if let Some((losing_player, fault_count, reason)) = ball_res {
                 let mut swap_serve = false;
+                let mut game_over = false;
+                crowd.reset();
+                rally.hits = 0;
 
                 if let Some(losing_player) = losing_player {
-                    swap_serve = add_point_to_score(&mut score, !is_left_player_id(losing_player));
+                    let outcome =
+                        add_point_to_score(&mut score, !is_left_player_id(losing_player), &config);
+                    swap_serve = should_swap_serve(&score, outcome, &config);
+                    // no serve on a finished match, even if the winner lookup below fails
+                    game_over = outcome == PointOutcome::MatchWon;
+
+                    if outcome != PointOutcome::Point {
+                        if let Some((winner, _)) = player_q.iter().find(|(p, _)| {
+                            is_left_player_id(p.id) != is_left_player_id(losing_player)
+                        }) {
+                            if outcome == PointOutcome::MatchWon {
+                                ev_w_game_over.send(GameOverEvt {
+                                    winner_id: winner.id,
+                                    loser_id: losing_player,
+                                });
+                            } else {
+                                ev_w_game_won.send(GameWonEvt {
+                                    winner_id: winner.id,
+                                    loser_id: losing_player,
+                                });
+                            }
+                        }
+                    }
                     debug!(
                         "Player {} has lost a point to {}! (bounce_count: {})",
                         losing_player, reason, ev.bounce_count
                     );
+                    ev_w_rumble.send(RumbleEvt {
+                        player_id: losing_player,
+                        kind: RumbleKind::PointLost,
+                    });
+
+                    if let Some((_, mut combo)) =
+                        combo_q.iter_mut().find(|(p, _)| p.id == losing_player)
+                    {
+                        combo.reset();
+                    }
+                } else if let BallStatus::Fault(..) = *status {
+                    ev_w_rumble.send(RumbleEvt {
+                        player_id: ends.get_player_id(serving_region.0.is_left()),
+                        kind: RumbleKind::Fault,
+                    });
                 }
 
                 *status = BallStatus::Used;
-                commands.entity(ev.ball_e).insert(Animator::new(
-                    Tween::new(
-                        EaseFunction::QuadraticIn,
-                        TweeningType::Once,
-                        Duration::from_millis(450),
-                        TransformScaleLens {
-                            start: ball_t.scale,
-                            end: Vec3::ZERO,
-                        },
-                    )
-                    .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
-                ));
+                let scale_out = Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(450),
+                    TransformScaleLens {
+                        start: ball_t.scale,
+                        end: Vec3::ZERO,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into());
+                let freeze_sec = if losing_player.is_some() {
+                    dead_ball.point_freeze_sec.max(0.)
+                } else {
+                    0.
+                };
+
+                if freeze_sec > 0. {
+                    ball.dir = Vec2::ZERO;
+                    let half_dur = Duration::from_secs_f32(freeze_sec / 2.);
+                    let highlight_scale = ball_t.scale * 1.4;
+                    commands.entity(ev.ball_e).insert(Animator::new(
+                        Tween::new(
+                            EaseFunction::QuadraticOut,
+                            TweeningType::Once,
+                            half_dur,
+                            TransformScaleLens {
+                                start: ball_t.scale,
+                                end: highlight_scale,
+                            },
+                        )
+                        .then(Tween::new(
+                            EaseFunction::QuadraticIn,
+                            TweeningType::Once,
+                            half_dur,
+                            TransformScaleLens {
+                                start: highlight_scale,
+                                end: ball_t.scale,
+                            },
+                        ))
+                        .then(scale_out),
+                    ));
+                } else {
+                    commands.entity(ev.ball_e).insert(Animator::new(scale_out));
+                }
 
                 if let Ok(e) = entity_q.get(ball.trail_e.unwrap()) {
                     commands.entity(e).insert(FadeOutTrail {
@@ -606,21 +1245,528 @@ fn on_ball_bounced(
 
                 if swap_serve {
                     serving_region.0 = if serving_region.0.is_left() {
-                        CourtRegion::get_random_right()
+                        CourtRegion::get_random_right(&mut rng.0)
                     } else {
-                        CourtRegion::get_random_left()
+                        CourtRegion::get_random_left(&mut rng.0)
                     };
+                    ev_w_serve_changed.send(ServeChangedEvt {
+                        serving_player_id: ends.get_player_id(serving_region.0.is_left()),
+                    });
+                } else if !practice.enabled && is_golden_point(&score, &config) {
+                    let serving_player_id = ends.get_player_id(serving_region.0.is_left());
+
+                    // the receiver picks the side for the deciding point by where they stand
+                    if let Some((_, receiver_t)) = player_q.iter().find(|(p, _)| {
+                        is_left_player_id(p.id) != is_left_player_id(serving_player_id)
+                    }) {
+                        serving_region.0 = CourtRegion::from_sides(
+                            serving_region.0.is_left(),
+                            receiver_t.translation.y < 0.,
+                        );
+                    }
+
+                    ev_w_golden_point.send(GoldenPointEvt { serving_player_id });
                 }
 
-                spawn_ball(
-                    &mut commands,
-                    &asset_server,
-                    serving_region.0,
-                    fault_count,
-                    serving_region.0.get_player_id(),
-                    &court_set,
-                );
+                if !game_over {
+                    commands.insert_resource(PendingServe {
+                        timer: Timer::from_seconds(dead_ball.delay_sec + freeze_sec, false),
+                        fault_count,
+                    });
+                }
             }
         }
     }
 }
+
+// (losing player, fault count of the next serve, reason) once the point or the serve is over
+// too many bounces go against the lowest id on the bounce side, so doubles partners share the point
+fn get_ball_result(
+    status: BallStatus,
+    bounce_count: usize,
+    bounce_side: f32,
+    netted: bool,
+    out: bool,
+    player_ids: &[usize],
+    ends: &Ends,
+    config: &MatchConfig,
+) -> Option<(Option<usize>, u8, &'static str)> {
+    match status {
+        BallStatus::Fault(count, player_id) => {
+            if config.fault_limit.is_reached(count) {
+                Some((Some(player_id), 0, "too many faults"))
+            } else {
+                // re-serve from the same region
+                Some((None, count, "fault"))
+            }
+        }
+        BallStatus::Let(count, _) => Some((None, count, "let")),
+        BallStatus::Rally(player_id) => {
+            if netted {
+                Some((Some(player_id), 0, "hitting the net"))
+            } else if out && bounce_count == 1 {
+                Some((Some(player_id), 0, "shooting out of bounds"))
+            } else if bounce_count > config.bounce_limit || (bounce_count > 1 && out) {
+                // the side the ball bounced on loses - whoever plays there after changing ends
+                let is_left_bounce = bounce_side < 0.;
+                let losing_player = player_ids
+                    .iter()
+                    .copied()
+                    .filter(|id| ends.is_left(*id) == is_left_bounce)
+                    .min()
+                    .unwrap_or_else(|| ends.get_player_id(is_left_bounce));
+
+                Some((Some(losing_player), 0, "too many bounces"))
+            } else {
+                None
+            }
+        }
+        BallStatus::Serve(..) | BallStatus::Used => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_config::ScoringMode;
+    use bevy::app::Events;
+
+    fn get_bounce_test_app(config: MatchConfig) -> App {
+        let mut app = App::new();
+        app.add_event::<BallBouncedEvt>()
+            .add_event::<GoldenPointEvt>()
+            .add_event::<RumbleEvt>()
+            .add_event::<GameWonEvt>()
+            .add_event::<GameOverEvt>()
+            .add_event::<AceEvt>()
+            .add_event::<ServeChangedEvt>()
+            .init_resource::<DeadBallSettings>()
+            .insert_resource(ServingRegion(CourtRegion::BottomLeft))
+            .init_resource::<Score>()
+            .insert_resource(CourtSettings {
+                left: -550.,
+                right: 550.,
+                top: 300.,
+                bottom: -300.,
+                base_region_size: Vec3::ONE,
+                region_x: 275.,
+            })
+            .insert_resource(config)
+            .init_resource::<CrowdIntensity>()
+            .init_resource::<GameRng>()
+            .init_resource::<ServePractice>()
+            .init_resource::<RallyCounter>()
+            .init_resource::<Ends>()
+            .add_system(on_ball_bounced);
+
+        for id in 1..=2 {
+            let aim_e = app.world.spawn().id();
+            app.world
+                .spawn()
+                .insert(Player {
+                    id,
+                    aim_e,
+                    aim_charge_e: aim_e,
+                    handedness: Handedness::Right,
+                    left_side: is_left_player_id(id),
+                })
+                .insert(Combo::default())
+                .insert(Transform::default());
+        }
+
+        app
+    }
+
+    // lands out of bounds on the left
+    fn send_ball_bounce(app: &mut App, status: BallStatus) -> Entity {
+        let trail_e = app.world.spawn().id();
+        let mut ball = Ball::default();
+        ball.trail_e = Some(trail_e);
+        ball.hit_count = 3;
+        let ball_e = app
+            .world
+            .spawn()
+            .insert(ball)
+            .insert(status)
+            .insert(Transform::from_xyz(-10_000., 0., 0.))
+            .id();
+
+        app.world
+            .get_resource_mut::<Events<BallBouncedEvt>>()
+            .unwrap()
+            .send(BallBouncedEvt {
+                ball_e,
+                bounce_count: 1,
+                side: -1.,
+                pos: Vec2::new(-10_000., 0.),
+            });
+        app.update();
+
+        ball_e
+    }
+
+    #[test]
+    fn point_queues_next_serve() {
+        let config = MatchConfig {
+            scoring_mode: ScoringMode::FirstTo(5),
+            ..Default::default()
+        };
+        let mut app = get_bounce_test_app(config);
+        let ball_e = send_ball_bounce(&mut app, BallStatus::Rally(2));
+
+        assert_eq!(
+            app.world
+                .get_resource::<Score>()
+                .unwrap()
+                .left_player
+                .points,
+            1
+        );
+        assert_eq!(
+            *app.world.get::<BallStatus>(ball_e).unwrap(),
+            BallStatus::Used
+        );
+        assert!(app.world.get_resource::<PendingServe>().is_some());
+    }
+
+    #[test]
+    fn winning_point_spawns_no_serve() {
+        let config = MatchConfig {
+            scoring_mode: ScoringMode::FirstTo(5),
+            ..Default::default()
+        };
+        let mut app = get_bounce_test_app(config);
+        app.world
+            .get_resource_mut::<Score>()
+            .unwrap()
+            .left_player
+            .points = 4;
+        let ball_e = send_ball_bounce(&mut app, BallStatus::Rally(2));
+
+        assert_eq!(
+            app.world
+                .get_resource::<Score>()
+                .unwrap()
+                .left_player
+                .points,
+            5
+        );
+        assert_eq!(
+            *app.world.get::<BallStatus>(ball_e).unwrap(),
+            BallStatus::Used
+        );
+        assert!(app.world.get_resource::<PendingServe>().is_none());
+        assert_eq!(app.world.query::<&Ball>().iter(&app.world).count(), 1);
+
+        let game_over_evts = app.world.get_resource::<Events<GameOverEvt>>().unwrap();
+        let winners: Vec<usize> = game_over_evts
+            .get_reader()
+            .iter(game_over_evts)
+            .map(|ev| ev.winner_id)
+            .collect();
+        assert_eq!(winners, vec![1]);
+    }
+
+    #[test]
+    fn fault_limit_awards_the_point() {
+        let config = MatchConfig {
+            scoring_mode: ScoringMode::FirstTo(5),
+            ..Default::default()
+        };
+        let mut app = get_bounce_test_app(config);
+
+        send_ball_bounce(&mut app, BallStatus::Fault(1, 1));
+        let score = app.world.get_resource::<Score>().unwrap();
+        assert_eq!(score.right_player.points, 0);
+        assert_eq!(
+            app.world
+                .get_resource::<PendingServe>()
+                .unwrap()
+                .fault_count,
+            1
+        );
+
+        send_ball_bounce(&mut app, BallStatus::Fault(2, 1));
+        let score = app.world.get_resource::<Score>().unwrap();
+        assert_eq!(score.right_player.points, 1);
+        assert_eq!(
+            app.world
+                .get_resource::<PendingServe>()
+                .unwrap()
+                .fault_count,
+            0
+        );
+    }
+
+    const SINGLES_IDS: [usize; 2] = [1, 2];
+    const DOUBLES_IDS: [usize; 4] = [1, 2, 3, 4];
+
+    #[test]
+    fn double_fault_goes_against_the_server() {
+        let config = MatchConfig::default();
+        assert_eq!(
+            get_ball_result(
+                BallStatus::Fault(1, 3),
+                1,
+                1.,
+                false,
+                false,
+                &DOUBLES_IDS,
+                &Ends::default(),
+                &config
+            ),
+            Some((None, 1, "fault"))
+        );
+        assert_eq!(
+            get_ball_result(
+                BallStatus::Fault(2, 3),
+                1,
+                1.,
+                false,
+                false,
+                &DOUBLES_IDS,
+                &Ends::default(),
+                &config
+            ),
+            Some((Some(3), 0, "too many faults"))
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_goes_against_the_hitter() {
+        let config = MatchConfig::default();
+
+        for (ids, hitter) in [(&SINGLES_IDS[..], 2), (&DOUBLES_IDS[..], 4)] {
+            assert_eq!(
+                get_ball_result(
+                    BallStatus::Rally(hitter),
+                    1,
+                    -1.,
+                    false,
+                    true,
+                    ids,
+                    &Ends::default(),
+                    &config
+                ),
+                Some((Some(hitter), 0, "shooting out of bounds"))
+            );
+        }
+    }
+
+    #[test]
+    fn netted_ball_goes_against_the_hitter() {
+        let config = MatchConfig::default();
+        assert_eq!(
+            get_ball_result(
+                BallStatus::Rally(1),
+                1,
+                -1.,
+                true,
+                false,
+                &SINGLES_IDS,
+                &Ends::default(),
+                &config
+            ),
+            Some((Some(1), 0, "hitting the net"))
+        );
+    }
+
+    #[test]
+    fn double_bounce_goes_against_the_bounce_side() {
+        let config = MatchConfig::default();
+
+        // the ball is still in play after a single bounce
+        assert_eq!(
+            get_ball_result(
+                BallStatus::Rally(2),
+                1,
+                -1.,
+                false,
+                false,
+                &DOUBLES_IDS,
+                &Ends::default(),
+                &config
+            ),
+            None
+        );
+
+        // ids 1 & 3 play on the left, 2 & 4 on the right
+        for (ids, side, loser) in [
+            (&SINGLES_IDS[..], -1., 1),
+            (&SINGLES_IDS[..], 1., 2),
+            (&DOUBLES_IDS[..], -1., 1),
+            (&DOUBLES_IDS[..], 1., 2),
+            (&[3, 4][..], -1., 3),
+            (&[3, 4][..], 1., 4),
+        ] {
+            assert_eq!(
+                get_ball_result(
+                    BallStatus::Rally(4),
+                    2,
+                    side,
+                    false,
+                    false,
+                    ids,
+                    &Ends::default(),
+                    &config
+                ),
+                Some((Some(loser), 0, "too many bounces"))
+            );
+        }
+    }
+
+    #[test]
+    fn double_bounce_follows_the_changed_ends() {
+        let config = MatchConfig::default();
+        let ends = Ends { swapped: true };
+
+        for (ids, side, loser) in [
+            (&SINGLES_IDS[..], -1., 2),
+            (&SINGLES_IDS[..], 1., 1),
+            (&DOUBLES_IDS[..], -1., 2),
+            (&[3][..], -1., 2),
+        ] {
+            assert_eq!(
+                get_ball_result(
+                    BallStatus::Rally(1),
+                    2,
+                    side,
+                    false,
+                    false,
+                    ids,
+                    &ends,
+                    &config
+                ),
+                Some((Some(loser), 0, "too many bounces"))
+            );
+        }
+    }
+
+    #[test]
+    fn circle_hitbox_reaches_all_around() {
+        let hitbox = SwingHitbox::Circle;
+        assert!(hitbox.is_in_reach(Vec2::new(90., 0.), Vec2::X, SWING_RADIUS));
+        assert!(hitbox.is_in_reach(Vec2::new(-90., 0.), Vec2::X, SWING_RADIUS));
+    }
+
+    #[test]
+    fn forward_hitbox_limits_the_back_reach() {
+        let hitbox = SwingHitbox::Forward(0.25);
+        // in front & to the side
+        assert!(hitbox.is_in_reach(Vec2::new(90., 0.), Vec2::X, SWING_RADIUS));
+        assert!(hitbox.is_in_reach(Vec2::new(0., 90.), Vec2::X, SWING_RADIUS));
+        // just behind
+        assert!(hitbox.is_in_reach(Vec2::new(-20., 0.), Vec2::X, SWING_RADIUS));
+        // too far behind
+        assert!(!hitbox.is_in_reach(Vec2::new(-30., 0.), Vec2::X, SWING_RADIUS));
+        // the aim doesn't have to be normalized
+        assert!(!hitbox.is_in_reach(Vec2::new(-30., 0.), Vec2::X * 0.3, SWING_RADIUS));
+    }
+
+    #[test]
+    fn oscillating_movement_keeps_the_animation() {
+        let settings = MoveAnimationSettings::default();
+        let mut idle_sec = 0.;
+
+        // stop & go every frame
+        for i in 0..120 {
+            let speed = if i % 2 == 0 { 0. } else { 100. };
+            assert_eq!(
+                settings.get_debounced_animation(
+                    &PlayerAnimation::Walking,
+                    speed,
+                    false,
+                    &mut idle_sec,
+                    1. / 60.
+                ),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn stopping_goes_idle_after_the_debounce() {
+        let settings = MoveAnimationSettings::default();
+        let mut idle_sec = 0.;
+        let dt = 1. / 60.;
+        let mut frames = 0;
+
+        while settings
+            .get_debounced_animation(&PlayerAnimation::Walking, 0., false, &mut idle_sec, dt)
+            .is_none()
+        {
+            frames += 1;
+            assert!(frames < 60);
+        }
+
+        assert!(frames as f32 * dt < settings.idle_debounce_sec);
+        assert!((frames + 1) as f32 * dt >= settings.idle_debounce_sec);
+    }
+
+    #[test]
+    fn tap_jump_reaches_min_height() {
+        let jump = JumpStats::default();
+        assert_eq!(jump.get_apex(0.), PLAYER_JUMP_HEIGHT_MIN);
+        assert_eq!(
+            jump.get_reach(0.),
+            SWING_REACH_HEIGHT + PLAYER_JUMP_HEIGHT_MIN
+        );
+    }
+
+    #[test]
+    fn faster_jump_reaches_higher() {
+        let base = JumpStats::default();
+        let springy = JumpStats {
+            velocity: PLAYER_JUMP_VEL_BASE * 1.3,
+            ..Default::default()
+        };
+        assert!(springy.get_reach(1.) > base.get_reach(1.));
+    }
+
+    #[test]
+    fn jump_peaks_at_apex_and_lands() {
+        let mut jump = JumpStats::default();
+        jump.start(1.);
+        let apex = jump.get_apex(1.);
+        let mut max_height: f32 = 0.;
+
+        for _ in 0..600 {
+            jump.step(1. / 600.);
+            max_height = max_height.max(jump.height);
+        }
+
+        assert!((max_height - apex).abs() < 1.);
+        assert_eq!(jump.height, 0.);
+        assert_eq!(jump.current_velocity, 0.);
+    }
+
+    #[test]
+    fn active_swing_does_not_restart_the_jump() {
+        let mut jump = JumpStats::default();
+        let apex = jump.get_apex(1.);
+        let dt = 1. / 60.;
+
+        // the swing system runs every frame of the Active window
+        for _ in 0..30 {
+            jump.start(1.);
+            jump.step(dt);
+            assert!(jump.height <= apex + 0.01);
+        }
+
+        assert_eq!(jump.get_apex_ratio(), 1.);
+    }
+
+    #[test]
+    fn doubles_partners_keep_their_own_handedness() {
+        let mut handedness = PlayerHandedness::default();
+        handedness.set(3, Handedness::Left);
+
+        // same side, different hands
+        assert_eq!(handedness.get(1), Handedness::Right);
+        assert_eq!(handedness.get(3), Handedness::Left);
+        assert_ne!(
+            is_facing_right(true, handedness.get(1)),
+            is_facing_right(true, handedness.get(3))
+        );
+    }
+}
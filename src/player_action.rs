@@ -2,9 +2,9 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
-use bevy_time::{ScaledTime, ScaledTimeDelta};
 
 use crate::{
+    game_speed::GameTime,
     player::{PlayerDash, PlayerSwing},
     GameState,
 };
@@ -65,7 +65,7 @@ pub trait ActionTimer<TActiveData: Default> {
 
 fn handle_action_cooldown<T: ActionTimer<TActiveData> + Component, TActiveData: Default>(
     mut query: Query<&mut T>,
-    time: ScaledTime,
+    time: GameTime,
 ) {
     for mut activity in query.iter_mut() {
         activity.handle_action_timer(time.scaled_delta());
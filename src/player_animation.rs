@@ -1,14 +1,19 @@
 use crate::player::{get_swing_multiplier, Player, PlayerSwing};
 use crate::GameState;
 use crate::{
-    animation::TransformRotation,
-    player::{PlayerDash, SwingRangeSprite, SWING_LABEL},
+    animation::{ReduceMotion, TransformRotation},
+    ball::BallHitEvt,
+    game_speed::GameTime,
+    palette::{Palette, PaletteColor},
+    particle::EffectSettings,
+    player::{JumpStats, PlayerDash, SwingRangeSprite, SWING_LABEL, SWING_RADIUS},
     player_action::PlayerActionStatus,
 };
 use bevy::{math::Vec2, prelude::*};
 use bevy_inspector_egui::Inspectable;
-use bevy_time::{ScaledTime, ScaledTimeDelta};
-use bevy_tweening::lens::{TransformPositionLens, TransformRotationLens, TransformScaleLens};
+use bevy_tweening::lens::{
+    SpriteColorLens, TransformPositionLens, TransformRotationLens, TransformScaleLens,
+};
 use bevy_tweening::*;
 use interpolation::EaseFunction;
 use std::time::Duration;
@@ -16,16 +21,58 @@ use std::time::Duration;
 pub struct PlayerAnimationPlugin;
 impl Plugin for PlayerAnimationPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_system(animate.after(SWING_LABEL))
+        app.init_resource::<SquashStretchSettings>()
+            .add_system(animate.after(SWING_LABEL))
             .add_system(unblock_animation)
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
                     .with_system(animate_dash_state_ui)
-                    .with_system(animate_swing_charge_ui),
+                    .with_system(animate_swing_range_ui)
+                    .with_system(animate_swing_charge_ui)
+                    .with_system(sync_swing_range_spin)
+                    .with_system(sync_face_flip)
+                    .with_system(glow_hitter_swing_range),
             );
     }
 }
 
+// body scale targets, 1 is no squash/stretch
+pub struct SquashStretchSettings {
+    pub shot_scale: f32,
+    pub dash_scale: f32,
+    pub disappointed_scale: Vec2,
+    // 0 turns the squash/stretch off, 1 is the default juice
+    pub intensity: f32,
+}
+
+impl Default for SquashStretchSettings {
+    fn default() -> Self {
+        Self {
+            shot_scale: 1.8,
+            dash_scale: 1.3,
+            disappointed_scale: Vec2::new(1.15, 0.8),
+            intensity: 1.,
+        }
+    }
+}
+
+impl SquashStretchSettings {
+    // clamped so the sprite never inverts or vanishes
+    pub fn get_scale(&self, scale: f32, reduce_motion: &ReduceMotion) -> f32 {
+        reduce_motion
+            .soften(1. + (scale - 1.) * self.intensity.max(0.))
+            .clamp(0.25, 3.)
+    }
+
+    // jumps higher than a base full charge one stretch more, smaller ones keep the usual shot stretch
+    pub fn get_shot_scale(&self, jump_apex_ratio: f32, reduce_motion: &ReduceMotion) -> f32 {
+        self.get_scale(
+            1. + (self.shot_scale - 1.) * jump_apex_ratio.max(1.),
+            reduce_motion,
+        )
+    }
+}
+
 #[derive(Default, Component, Inspectable, PartialEq, Debug)]
 pub enum PlayerAnimation {
     #[default]
@@ -35,6 +82,7 @@ pub enum PlayerAnimation {
     Dashing,
     Celebrating,
     Shooting,
+    Disappointed,
 }
 
 #[derive(Component, Inspectable)]
@@ -43,6 +91,7 @@ pub struct AgentAnimationData {
     pub face_e: Entity,
     pub body_e: Entity,
     pub body_root_e: Entity,
+    pub jump_root_e: Entity,
 }
 
 #[derive(Component, Inspectable)]
@@ -55,10 +104,13 @@ fn animate(
         &AgentAnimationData,
         Option<&AgentAnimationBlock>,
         ChangeTrackers<AgentAnimationData>,
+        Option<&JumpStats>,
     )>,
     mut animator_q: Query<(&mut Animator<Transform>, &Transform)>,
+    reduce_motion: Res<ReduceMotion>,
+    squash_stretch: Res<SquashStretchSettings>,
 ) {
-    for (anim_e, anim, block, anim_tracker) in player_anim_q.iter() {
+    for (anim_e, anim, block, anim_tracker, jump) in player_anim_q.iter() {
         if anim_tracker.is_changed() || anim_tracker.is_added() {
             if block.is_some() {
                 continue;
@@ -74,7 +126,14 @@ fn animate(
                     stop_anim_entities.push(anim.body_root_e);
 
                     if let Ok((mut animator, t)) = animator_q.get_mut(anim.body_e) {
-                        let (tween, dur) = get_body_scale_tween(t, 1.8, 300);
+                        let (tween, dur) = get_body_scale_tween(
+                            t,
+                            squash_stretch.get_shot_scale(
+                                jump.map_or(1., |j| j.get_apex_ratio()),
+                                &reduce_motion,
+                            ),
+                            300,
+                        );
                         animator.set_tweenable(tween);
                         animator.rewind();
                         animator.state = AnimatorState::Playing;
@@ -87,7 +146,11 @@ fn animate(
                     stop_anim_entities.push(anim.body_root_e);
 
                     if let Ok((mut animator, t)) = animator_q.get_mut(anim.body_e) {
-                        let (tween, dur) = get_body_scale_tween(t, 1.3, 220);
+                        let (tween, dur) = get_body_scale_tween(
+                            t,
+                            squash_stretch.get_scale(squash_stretch.dash_scale, &reduce_motion),
+                            220,
+                        );
                         animator.set_tweenable(tween);
                         animator.rewind();
                         animator.state = AnimatorState::Playing;
@@ -125,6 +188,33 @@ fn animate(
                     stop_anim_entities.push(anim.body_e);
                     body_root_tween = Some(get_move_tween(500, 20., 12.));
                 }
+                PlayerAnimation::Disappointed => {
+                    stop_anim_entities.push(anim.body_root_e);
+
+                    if let Ok((mut animator, t)) = animator_q.get_mut(anim.face_e) {
+                        animator.set_tweenable(get_disappointed_face_tween(t));
+                        animator.rewind();
+                        animator.state = AnimatorState::Playing;
+                    }
+
+                    if let Ok((mut animator, t)) = animator_q.get_mut(anim.body_e) {
+                        let (tween, dur) = get_body_squash_tween(
+                            t,
+                            700,
+                            Vec2::new(
+                                squash_stretch
+                                    .get_scale(squash_stretch.disappointed_scale.x, &reduce_motion),
+                                squash_stretch
+                                    .get_scale(squash_stretch.disappointed_scale.y, &reduce_motion),
+                            ),
+                        );
+                        animator.set_tweenable(tween);
+                        animator.rewind();
+                        animator.state = AnimatorState::Playing;
+
+                        commands.entity(anim_e).insert(AgentAnimationBlock(dur));
+                    }
+                }
             }
 
             for e in stop_anim_entities.iter() {
@@ -148,7 +238,7 @@ fn animate(
 fn unblock_animation(
     mut commands: Commands,
     mut block_q: Query<(Entity, &mut AgentAnimationBlock)>,
-    time: ScaledTime,
+    time: GameTime,
 ) {
     for (e, mut block) in block_q.iter_mut() {
         block.0 -= time.scaled_delta_seconds();
@@ -277,10 +367,60 @@ fn get_body_scale_tween(transform: &Transform, scale: f32, dur: u64) -> (Sequenc
     (t, 0.5)
 }
 
+fn get_body_squash_tween(
+    transform: &Transform,
+    dur: u64,
+    scale: Vec2,
+) -> (Sequence<Transform>, f32) {
+    let end = scale.extend(1.);
+    let t = Tween::new(
+        EaseFunction::QuadraticOut,
+        TweeningType::Once,
+        Duration::from_millis(dur / 3),
+        TransformScaleLens {
+            start: transform.scale,
+            end,
+        },
+    )
+    .then(Tween::new(
+        EaseFunction::QuadraticInOut,
+        TweeningType::Once,
+        Duration::from_millis(dur * 2 / 3),
+        TransformScaleLens {
+            start: end,
+            end: Vec3::ONE,
+        },
+    ));
+    (t, dur as f32 / 1000.)
+}
+
+fn get_disappointed_face_tween(transform: &Transform) -> Sequence<Transform> {
+    // hang the head
+    let end = Vec3::new(0., -10., transform.translation.z);
+    Tween::new(
+        EaseFunction::QuadraticOut,
+        TweeningType::Once,
+        Duration::from_millis(250),
+        TransformPositionLens {
+            start: transform.translation,
+            end,
+        },
+    )
+    .then(Tween::new(
+        EaseFunction::QuadraticInOut,
+        TweeningType::Once,
+        Duration::from_millis(450),
+        TransformPositionLens {
+            start: end,
+            end: Vec3::new(0., 0., transform.translation.z),
+        },
+    ))
+}
+
 fn animate_dash_state_ui(
     mut q: Query<(&Parent, &mut TransformRotation), With<SwingRangeSprite>>,
     dash_q: Query<&PlayerDash>,
-    time: ScaledTime,
+    time: GameTime,
 ) {
     for (parent, mut rot) in q.iter_mut() {
         if let Ok(dash) = dash_q.get(parent.0) {
@@ -301,21 +441,126 @@ fn animate_dash_state_ui(
     }
 }
 
+// the ring spins away from the way the player faces, which can change after spawn
+fn sync_swing_range_spin(
+    mut q: Query<(&Parent, &mut TransformRotation), With<SwingRangeSprite>>,
+    player_q: Query<&Player, Changed<Player>>,
+) {
+    for (parent, mut rot) in q.iter_mut() {
+        if let Ok(player) = player_q.get(parent.0) {
+            let sign = if player.is_facing_right() { -1. } else { 1. };
+
+            if rot.rotation_max_rad.signum() != sign {
+                rot.rotation_max_rad = -rot.rotation_max_rad;
+                rot.rotation_rad = -rot.rotation_rad;
+            }
+        }
+    }
+}
+
+// the face turns with the player after changing ends
+fn sync_face_flip(
+    player_q: Query<(&Player, &AgentAnimationData), Changed<Player>>,
+    mut sprite_q: Query<&mut Sprite>,
+) {
+    for (player, anim) in player_q.iter() {
+        if let Ok(mut sprite) = sprite_q.get_mut(anim.face_e) {
+            sprite.flip_x = !player.is_facing_right();
+        }
+    }
+}
+
+fn glow_hitter_swing_range(
+    mut commands: Commands,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    q: Query<(Entity, &Parent), With<SwingRangeSprite>>,
+    player_q: Query<&Player>,
+    effects: Res<EffectSettings>,
+    palette: Res<Palette>,
+) {
+    for ev in ev_r_hit.iter() {
+        if !effects.hit_ring_glow {
+            continue;
+        }
+
+        for (e, parent) in q.iter() {
+            if let Ok(player) = player_q.get(parent.0) {
+                if player.id == ev.player_id {
+                    commands.entity(e).insert(Animator::new(Tween::new(
+                        EaseFunction::QuadraticOut,
+                        TweeningType::Once,
+                        Duration::from_millis(400),
+                        SpriteColorLens {
+                            start: palette.get_color(&PaletteColor::Ball),
+                            end: palette.get_color(&PaletteColor::PlayerAccent(player.id)),
+                        },
+                    )));
+                }
+            }
+        }
+    }
+}
+
+fn animate_swing_range_ui(
+    mut q: Query<(&Parent, &mut Transform), With<SwingRangeSprite>>,
+    swing_q: Query<&PlayerSwing>,
+    time: GameTime,
+) {
+    for (parent, mut t) in q.iter_mut() {
+        if let Ok(swing) = swing_q.get(parent.0) {
+            let target_scale = swing.radius / SWING_RADIUS;
+            let scale =
+                t.scale.x + (target_scale - t.scale.x) * (time.scaled_delta_seconds() * 5.).min(1.);
+            t.scale = Vec2::splat(scale).extend(1.);
+        }
+    }
+}
+
 fn animate_swing_charge_ui(
     player_q: Query<(&Player, &PlayerSwing)>,
-    mut aim_charge_q: Query<&mut Transform>,
-    time: ScaledTime,
+    mut aim_charge_q: Query<(&mut Transform, &mut Sprite)>,
+    palette: Res<Palette>,
+    time: GameTime,
 ) {
+    let col = palette.get_color(&PaletteColor::PlayerCharge);
+
     for (player, player_swing) in player_q.iter() {
-        if let Ok(mut t) = aim_charge_q.get_mut(player.aim_charge_e) {
-            if let PlayerActionStatus::Charging(dur) = player_swing.status {
-                let scale = get_swing_multiplier(dur);
-                t.scale = Vec2::splat(scale).extend(1.);
-            } else if !matches!(player_swing.status, PlayerActionStatus::Active(_)) {
-                t.scale =
-                    Vec2::splat((t.scale.x - (time.scaled_delta_seconds() * 3.)).clamp(0., 1.))
-                        .extend(1.);
-            }
+        if let Ok((mut t, mut sprite)) = aim_charge_q.get_mut(player.aim_charge_e) {
+            let scale = if let PlayerActionStatus::Charging(dur) = player_swing.status {
+                get_swing_multiplier(dur)
+            } else {
+                // fired or cancelled
+                (t.scale.x - (time.scaled_delta_seconds() * 3.)).clamp(0., 1.)
+            };
+
+            t.scale = Vec2::splat(scale).extend(1.);
+            // fades in with the charge
+            sprite.color = col;
+            sprite.color.set_a(col.a() * (0.3 + 0.7 * scale));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_jump_keeps_the_shot_stretch() {
+        let settings = SquashStretchSettings::default();
+        let reduce_motion = ReduceMotion::default();
+        let base = settings.get_scale(settings.shot_scale, &reduce_motion);
+        assert_eq!(settings.get_shot_scale(1., &reduce_motion), base);
+        // smaller hops don't shrink it
+        assert_eq!(settings.get_shot_scale(0.3, &reduce_motion), base);
+    }
+
+    #[test]
+    fn bigger_jump_stretches_more_but_stays_clamped() {
+        let settings = SquashStretchSettings::default();
+        let reduce_motion = ReduceMotion::default();
+        let base = settings.get_shot_scale(1., &reduce_motion);
+        assert!(settings.get_shot_scale(1.5, &reduce_motion) > base);
+        assert_eq!(settings.get_shot_scale(100., &reduce_motion), 3.);
+    }
+}
@@ -1,11 +1,18 @@
 use crate::{
     ai_player_controller::AiPlayer,
-    input_binding::{InputAction, InputAxis, PlayerInput},
+    camera::GameCamera,
+    countdown::Countdown,
+    input_binding::{
+        DashIntents, DoubleTapDashSettings, InputAction, InputAxis, InputSettings,
+        MouseAimSettings, PlayerInput, DOUBLE_TAP_DASH_LABEL, MOUSE_AIM_PLAYER_ID,
+    },
     player::{
         get_swing_multiplier_clamped, Player, PlayerAim, PlayerDash, PlayerMovement, PlayerSwing,
-        SWING_LABEL,
+        SwingBufferSettings, SwingConfig, SwingTap, SWING_LABEL,
     },
     player_action::PlayerActionStatus,
+    serve::ServeApproach,
+    warm_up::WarmUp,
     GameState,
 };
 use bevy::prelude::*;
@@ -15,48 +22,128 @@ pub struct PlayerControllerPlugin;
 impl Plugin for PlayerControllerPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_system_set(
-            SystemSet::on_update(GameState::Game)
-                .with_system(process_player_input.label(SWING_LABEL)),
+            SystemSet::on_update(GameState::Game).with_system(
+                process_player_input
+                    .label(SWING_LABEL)
+                    .after(DOUBLE_TAP_DASH_LABEL),
+            ),
         );
     }
 }
 
+// button state shared by the action input & the mouse
+#[derive(Clone, Copy)]
+enum SwingInput {
+    Pressed,
+    Held(f32),
+    Released(f32),
+}
+
 fn process_player_input(
     input: Res<PlayerInput>,
+    input_settings: Res<InputSettings>,
+    buffer_settings: Res<SwingBufferSettings>,
+    swing_config: Res<SwingConfig>,
     mut q: Query<
         (
             &Player,
             &mut PlayerMovement,
             &mut PlayerDash,
             &mut PlayerSwing,
+            &GlobalTransform,
+            Option<&ServeApproach>,
         ),
         Without<AiPlayer>,
     >,
     mut aim_q: Query<&mut PlayerAim>,
+    warm_up: Option<Res<WarmUp>>,
+    countdown: Option<Res<Countdown>>,
+    mouse_aim: Res<MouseAimSettings>,
+    double_tap: Res<DoubleTapDashSettings>,
+    dash_intents: Res<DashIntents>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    cam_q: Query<&GlobalTransform, (With<GameCamera>, Without<Player>)>,
+    time: Res<Time>,
+    mut mouse_held_sec: Local<f32>,
 ) {
-    for (player, mut player_movement, mut player_dash, mut player_swing) in q.iter_mut() {
+    if warm_up.is_some() {
+        return;
+    }
+
+    let cursor_pos = if mouse_aim.enabled {
+        get_cursor_world_pos(&windows, &cam_q)
+    } else {
+        None
+    };
+    let mouse_swing = if !mouse_aim.enabled {
+        None
+    } else if mouse_buttons.just_pressed(MouseButton::Left) {
+        *mouse_held_sec = 0.;
+        Some(SwingInput::Pressed)
+    } else if mouse_buttons.pressed(MouseButton::Left) {
+        *mouse_held_sec += time.delta_seconds();
+        Some(SwingInput::Held(*mouse_held_sec))
+    } else if mouse_buttons.just_released(MouseButton::Left) {
+        Some(SwingInput::Released(*mouse_held_sec))
+    } else {
+        None
+    };
+
+    for (
+        player,
+        mut player_movement,
+        mut player_dash,
+        mut player_swing,
+        player_t,
+        serve_approach,
+    ) in q.iter_mut()
+    {
+        let uses_mouse = mouse_aim.enabled && player.id == MOUSE_AIM_PLAYER_ID;
+
         // movement
-        player_movement.raw_dir = if input.held(player.id, InputAction::LockPosition) {
-            Vec2::ZERO
+        player_movement.raw_dir =
+            if serve_approach.is_some() || input.held(player.id, InputAction::LockPosition) {
+                Vec2::ZERO
+            } else {
+                input_settings.get_xy_axes(&input, player.id, &InputAxis::MoveX, &InputAxis::MoveY)
+            };
+
+        // a zero dir falls back to the aim
+        let dash_dir = if double_tap.enabled {
+            dash_intents.0.get(&player.id).copied()
+        } else if input.just_pressed(player.id, InputAction::Dash) {
+            Some(player_movement.raw_dir)
         } else {
-            input.get_xy_axes_raw(player.id, &InputAxis::MoveX, &InputAxis::MoveY)
+            None
         };
 
         // aim
         if let Ok(mut player_aim) = aim_q.get_mut(player.aim_e) {
             // start with aim dir
             player_aim.raw_dir =
-                input.get_xy_axes_raw(player.id, &InputAxis::AimX, &InputAxis::AimY);
+                input_settings.get_xy_axes(&input, player.id, &InputAxis::AimX, &InputAxis::AimY);
             if player_aim.raw_dir == Vec2::ZERO {
                 // fallback to movement dir
-                player_aim.raw_dir =
-                    input.get_xy_axes_raw(player.id, &InputAxis::MoveX, &InputAxis::MoveY);
+                player_aim.raw_dir = input_settings.get_xy_axes(
+                    &input,
+                    player.id,
+                    &InputAxis::MoveX,
+                    &InputAxis::MoveY,
+                );
+            }
+
+            if uses_mouse {
+                if let Some(cursor_pos) = cursor_pos {
+                    player_aim.raw_dir =
+                        (cursor_pos - player_t.translation.truncate()).normalize_or_zero();
+                }
             }
 
             // dash
-            if input.just_pressed(player.id, InputAction::Dash) {
+            if let Some(dash_dir) = dash_dir {
                 if let PlayerActionStatus::Ready = player_dash.status {
-                    let dir = player_movement.raw_dir.normalize_or_zero();
+                    let dir = dash_dir.normalize_or_zero();
                     player_dash.status = PlayerActionStatus::Active(if dir != Vec2::ZERO {
                         dir
                     } else {
@@ -67,26 +154,76 @@ fn process_player_input(
             }
         }
 
+        // moving & aiming is fine during the countdown
+        if countdown.is_some() {
+            continue;
+        }
+
         // swing
-        // nice2have: on swing down cancel prev swing?
-        if let Some(input_action_state) =
-            input.get_button_action_state(player.id, &InputAction::Swing)
+        if swing_config.dash_cancels_swing
+            && dash_dir.is_some()
+            && matches!(
+                player_swing.status,
+                PlayerActionStatus::Charging(..) | PlayerActionStatus::Active(..)
+            )
         {
-            match input_action_state {
-                ActionState::Pressed => {
+            player_swing.start_cooldown();
+            continue;
+        }
+
+        // nice2have: on swing down cancel prev swing?
+        let swing_input = match mouse_swing {
+            Some(mouse_swing) if uses_mouse => Some(mouse_swing),
+            _ => match input.get_button_action_state(player.id, &InputAction::Swing) {
+                Some(ActionState::Pressed) => Some(SwingInput::Pressed),
+                Some(ActionState::Held(key_data)) => Some(SwingInput::Held(key_data.duration)),
+                Some(ActionState::Released(key_data)) => {
+                    Some(SwingInput::Released(key_data.duration))
+                }
+                _ => None,
+            },
+        };
+
+        if let Some(swing_input) = swing_input {
+            let cooldown = matches!(player_swing.status, PlayerActionStatus::Cooldown);
+
+            match swing_input {
+                SwingInput::Pressed if !cooldown => {
                     player_swing.status = PlayerActionStatus::Charging(0.);
                 }
-                ActionState::Held(key_date) => {
-                    player_swing.status = PlayerActionStatus::Charging(key_date.duration);
+                SwingInput::Held(duration) if !cooldown => {
+                    player_swing.status = PlayerActionStatus::Charging(duration);
                 }
-                ActionState::Released(key_data) => {
-                    if let PlayerActionStatus::Ready | PlayerActionStatus::Charging(..) =
-                        player_swing.status
-                    {
-                        player_swing.status = PlayerActionStatus::Active(
-                            get_swing_multiplier_clamped(key_data.duration),
-                        );
-                        player_swing.timer = Timer::from_seconds(player_swing.duration_sec, false);
+                SwingInput::Released(duration)
+                    if duration < swing_config.min_charge_sec
+                        && swing_config.tap == SwingTap::Ignore =>
+                {
+                    // the swing stays ready
+                    if let PlayerActionStatus::Charging(..) = player_swing.status {
+                        player_swing.status = PlayerActionStatus::Ready;
+                    }
+                }
+                SwingInput::Released(duration) => {
+                    let strength = if duration < swing_config.min_charge_sec {
+                        // block shot
+                        get_swing_multiplier_clamped(0.)
+                    } else {
+                        get_swing_multiplier_clamped(duration)
+                    };
+
+                    match player_swing.status {
+                        PlayerActionStatus::Ready | PlayerActionStatus::Charging(..) => {
+                            player_swing.status = PlayerActionStatus::Active(strength);
+                            player_swing.timer =
+                                Timer::from_seconds(player_swing.duration_sec, false);
+                        }
+                        PlayerActionStatus::Cooldown => {
+                            player_swing.buffered = Some((
+                                strength,
+                                Timer::from_seconds(buffer_settings.buffer_sec, false),
+                            ));
+                        }
+                        _ => {}
                     }
                 }
                 _ => {}
@@ -94,3 +231,16 @@ fn process_player_input(
         }
     }
 }
+
+// window coords have the origin in the bottom left corner, the camera is centered
+fn get_cursor_world_pos(
+    windows: &Windows,
+    cam_q: &Query<&GlobalTransform, (With<GameCamera>, Without<Player>)>,
+) -> Option<Vec2> {
+    let window = windows.get_primary()?;
+    let cursor = window.cursor_position()?;
+    let cam_t = cam_q.iter().next()?;
+    let size = Vec2::new(window.width(), window.height());
+
+    Some(cam_t.translation.truncate() + (cursor - size / 2.) * cam_t.scale.truncate())
+}
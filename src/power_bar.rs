@@ -0,0 +1,107 @@
+use crate::{
+    palette::{Palette, PaletteColor},
+    player::{get_swing_multiplier_clamped, Player, PlayerSwing},
+    player_action::PlayerActionStatus,
+};
+use bevy::prelude::*;
+
+const BAR_WIDTH: f32 = 70.;
+const BAR_HEIGHT: f32 = 8.;
+const BAR_OFFSET_Y: f32 = -65.;
+
+pub struct PowerBarPlugin;
+impl Plugin for PowerBarPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<PowerBarSettings>()
+            .add_system(spawn_power_bar)
+            .add_system(update_power_bar);
+    }
+}
+
+pub struct PowerBarSettings {
+    pub enabled: bool,
+}
+
+impl Default for PowerBarSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+#[derive(Component)]
+struct PowerBar {
+    fill_e: Entity,
+}
+
+fn spawn_power_bar(
+    mut commands: Commands,
+    player_q: Query<Entity, Added<Player>>,
+    palette: Res<Palette>,
+) {
+    for player_e in player_q.iter() {
+        let fill_e = commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                    color: palette.get_color(&PaletteColor::PlayerCharge),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., 0., 0.1),
+                visibility: Visibility { is_visible: false },
+                ..Default::default()
+            })
+            .insert(PaletteColor::PlayerCharge)
+            .insert(Name::new("PowerBarFill"))
+            .id();
+
+        let bar_e = commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                    color: palette.get_color(&PaletteColor::Shadow),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., BAR_OFFSET_Y, 0.5),
+                visibility: Visibility { is_visible: false },
+                ..Default::default()
+            })
+            .insert(PaletteColor::Shadow)
+            .insert(PowerBar { fill_e })
+            .insert(Name::new("PowerBar"))
+            .push_children(&[fill_e])
+            .id();
+
+        commands.entity(player_e).push_children(&[bar_e]);
+    }
+}
+
+fn update_power_bar(
+    player_q: Query<(&PlayerSwing, &Children)>,
+    mut bar_q: Query<(&PowerBar, &mut Visibility)>,
+    mut fill_q: Query<(&mut Transform, &mut Visibility), Without<PowerBar>>,
+    settings: Res<PowerBarSettings>,
+) {
+    for (swing, children) in player_q.iter() {
+        let charge = match swing.status {
+            PlayerActionStatus::Charging(dur) if settings.enabled => {
+                Some(get_swing_multiplier_clamped(dur))
+            }
+            _ => None,
+        };
+
+        for child in children.iter() {
+            if let Ok((bar, mut bar_visibility)) = bar_q.get_mut(*child) {
+                bar_visibility.is_visible = charge.is_some();
+
+                if let Ok((mut fill_t, mut fill_visibility)) = fill_q.get_mut(bar.fill_e) {
+                    fill_visibility.is_visible = charge.is_some();
+
+                    // fill from the left
+                    let charge = charge.unwrap_or(0.);
+                    fill_t.scale.x = charge;
+                    fill_t.translation.x = -BAR_WIDTH * (1. - charge) / 2.;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    input_binding::{InputAction, PlayerInput},
+    palette::PaletteColor,
+    reset::Persistent,
+};
+use bevy::prelude::*;
+
+pub struct PracticePlugin;
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<ServePractice>()
+            .add_startup_system(setup)
+            .add_system(toggle_serve_practice)
+            .add_system(update_practice_ui);
+    }
+}
+
+// faults & bounces don't score, the server just keeps serving from the same region
+#[derive(Default)]
+pub struct ServePractice {
+    pub enabled: bool,
+    pub landed: usize,
+    pub faulted: usize,
+    // training aid - serves go to the middle of the service box whatever the aim, so the returner can focus on timing
+    pub serve_to_center: bool,
+}
+
+#[derive(Component)]
+struct PracticeText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(20.),
+                    bottom: Val::Px(20.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+                Default::default(),
+            ),
+            visibility: Visibility { is_visible: false },
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(PracticeText)
+        .insert(Name::new("PracticeText"))
+        .insert(Persistent);
+}
+
+fn toggle_serve_practice(input: Res<PlayerInput>, mut practice: ResMut<ServePractice>) {
+    for id in 1..=4 {
+        if input.just_pressed(id, InputAction::ToggleServePractice) {
+            *practice = ServePractice {
+                enabled: !practice.enabled,
+                serve_to_center: practice.serve_to_center,
+                ..Default::default()
+            };
+            info!("Serve practice: {}", practice.enabled);
+
+            break;
+        }
+    }
+}
+
+fn update_practice_ui(
+    practice: Res<ServePractice>,
+    mut text_q: Query<(&mut Text, &mut Visibility), With<PracticeText>>,
+) {
+    if practice.is_changed() {
+        for (mut text, mut visibility) in text_q.iter_mut() {
+            visibility.is_visible = practice.enabled;
+            text.sections[0].value = format!(
+                "serves in: {} | faults: {}",
+                practice.landed, practice.faulted
+            );
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    ball::BallHitEvt,
+    palette::{Palette, PaletteColor},
+    reset::Persistent,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_tweening::{lens::TextColorLens, Animator, EaseFunction, Tween, TweeningType};
+use std::time::Duration;
+
+pub struct RallyPlugin;
+impl Plugin for RallyPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<RallyCounter>()
+            .init_resource::<RallySettings>()
+            .add_startup_system(setup)
+            .add_system_set(SystemSet::on_enter(GameState::Game).with_system(reset_rally))
+            .add_system(on_ball_hit)
+            .add_system(update_rally_ui);
+    }
+}
+
+pub struct RallySettings {
+    // flash every n hits
+    pub milestone: usize,
+}
+
+impl Default for RallySettings {
+    fn default() -> Self {
+        Self { milestone: 10 }
+    }
+}
+
+// hits in the current point, the serve included
+// reset by on_ball_bounced whenever a point/serve ends
+#[derive(Default)]
+pub struct RallyCounter {
+    pub hits: usize,
+}
+
+#[derive(Component)]
+struct RallyText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(10.),
+                    left: Val::Px(0.),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.), Val::Auto),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(Name::new("RallyCounter"))
+        .insert(Persistent)
+        .with_children(|b| {
+            b.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            })
+            .insert(PaletteColor::Text)
+            .insert(RallyText);
+        });
+}
+
+fn reset_rally(mut rally: ResMut<RallyCounter>) {
+    rally.hits = 0;
+}
+
+fn on_ball_hit(mut ev_r_hit: EventReader<BallHitEvt>, mut rally: ResMut<RallyCounter>) {
+    for _ev in ev_r_hit.iter() {
+        rally.hits += 1;
+    }
+}
+
+fn update_rally_ui(
+    mut commands: Commands,
+    rally: Res<RallyCounter>,
+    mut text_q: Query<(Entity, &mut Text), With<RallyText>>,
+    palette: Res<Palette>,
+    settings: Res<RallySettings>,
+) {
+    if !rally.is_changed() {
+        return;
+    }
+
+    let is_milestone =
+        rally.hits > 0 && settings.milestone > 0 && rally.hits % settings.milestone == 0;
+
+    for (e, mut text) in text_q.iter_mut() {
+        // a single hit is just the serve
+        text.sections[0].value = if rally.hits > 1 {
+            rally.hits.to_string()
+        } else {
+            String::new()
+        };
+
+        if is_milestone {
+            commands.entity(e).insert(Animator::new(Tween::new(
+                EaseFunction::QuadraticIn,
+                TweeningType::Once,
+                Duration::from_millis(800),
+                TextColorLens {
+                    start: palette.get_color(&PaletteColor::Ball),
+                    end: palette.get_color(&PaletteColor::Text),
+                    section: 0,
+                },
+            )));
+        }
+    }
+}
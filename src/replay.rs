@@ -0,0 +1,364 @@
+use crate::{
+    ball::{Ball, BallBouncedEvt, BallHitEvt, BallStatus, CurrentBall},
+    game_speed::GameTime,
+    input_binding::{InputAction, PlayerInput},
+    match_config::MatchConfig,
+    player::Player,
+    GameState,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+// bump on any format change & migrate in load_replay
+pub const REPLAY_VERSION: u32 = 1;
+// relative to the executable
+const REPLAY_EXPORT_PATH: &str = "replays/last_point.ron";
+
+pub struct ReplayPlugin;
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<ReplayRecorder>()
+            .add_startup_system(load_replay_arg)
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(start_recording)
+                    .with_system(record_frame)
+                    .with_system(play_replay),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Game).with_system(despawn_ghosts))
+            .add_system(export_replay);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayData {
+    pub version: u32,
+    // the match seed, so net cords etc. can be reproduced
+    pub seed: u64,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ReplayFrame {
+    pub time: f32,
+    pub balls: Vec<[f32; 3]>,
+    // (id, pos)
+    pub players: Vec<(usize, [f32; 3])>,
+    pub events: Vec<ReplayEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ReplayEvent {
+    Hit { player_id: usize, strength: f32 },
+    Bounce { bounce_count: usize },
+}
+
+// holds the current point, the last finished point stays around until the next serve
+#[derive(Default)]
+pub struct ReplayRecorder {
+    time: f32,
+    frames: Vec<ReplayFrame>,
+}
+
+// a loaded replay looping as ghosts over the match
+pub struct ReplayPlayback {
+    data: ReplayData,
+    time: f32,
+}
+
+// index into the frame's balls/players
+#[derive(Component, Clone, Copy, PartialEq)]
+enum ReplayGhost {
+    Ball(usize),
+    Player(usize),
+}
+
+// `--replay <path>` plays the recorded point back & replays the recorded match seed
+fn get_replay_arg() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|a| a != "--replay").skip(1);
+    args.next().map(PathBuf::from)
+}
+
+// the working dir differs between `cargo run` & a shipped build
+fn get_export_path() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_default()
+        .join(REPLAY_EXPORT_PATH)
+}
+
+pub fn save_replay(path: impl AsRef<Path>, data: &ReplayData) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.as_ref().parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let ron = ron::ser::to_string_pretty(data, ron::ser::PrettyConfig::default())?;
+    fs::write(path, ron)?;
+
+    Ok(())
+}
+
+pub fn load_replay(path: impl AsRef<Path>) -> Result<ReplayData, Box<dyn Error>> {
+    let data: ReplayData = ron::from_str(&fs::read_to_string(path)?)?;
+
+    if data.version != REPLAY_VERSION {
+        return Err(format!("Unsupported replay version {}", data.version).into());
+    }
+
+    Ok(data)
+}
+
+// a fresh serve starts a new recording
+fn start_recording(
+    status_q: Query<&BallStatus, Added<BallStatus>>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    if status_q.iter().any(|s| matches!(s, BallStatus::Serve(..))) {
+        *recorder = ReplayRecorder::default();
+    }
+}
+
+fn record_frame(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    mut ev_r_bounce: EventReader<BallBouncedEvt>,
+    ball_q: Query<(&BallStatus, &GlobalTransform), With<Ball>>,
+    player_q: Query<(&Player, &GlobalTransform)>,
+    current_ball: Res<CurrentBall>,
+    time: GameTime,
+) {
+    let mut events: Vec<ReplayEvent> = ev_r_hit
+        .iter()
+        .map(|ev| ReplayEvent::Hit {
+            player_id: ev.player_id,
+            strength: ev.strength,
+        })
+        .collect();
+    events.extend(ev_r_bounce.iter().map(|ev| ReplayEvent::Bounce {
+        bounce_count: ev.bounce_count,
+    }));
+
+    // stop once the point is over
+    if !current_ball.is_live() && events.is_empty() {
+        return;
+    }
+
+    let balls: Vec<_> = ball_q
+        .iter()
+        .filter(|(s, _)| !matches!(s, BallStatus::Used))
+        .map(|(_, t)| t.translation.into())
+        .collect();
+
+    recorder.time += time.scaled_delta_seconds();
+    let frame = ReplayFrame {
+        time: recorder.time,
+        balls,
+        players: player_q
+            .iter()
+            .map(|(p, t)| (p.id, t.translation.into()))
+            .collect(),
+        events,
+    };
+    recorder.frames.push(frame);
+}
+
+fn load_replay_arg(mut commands: Commands, mut config: ResMut<MatchConfig>) {
+    if let Some(path) = get_replay_arg() {
+        match load_replay(&path) {
+            Ok(data) => {
+                info!(
+                    "Loaded replay {} ({} frames), seed: {}",
+                    path.display(),
+                    data.frames.len(),
+                    data.seed
+                );
+                config.seed = data.seed;
+                commands.insert_resource(ReplayPlayback { data, time: 0. });
+            }
+            Err(e) => error!("Failed to load replay {}: {}", path.display(), e),
+        }
+    }
+}
+
+// the last frame recorded at or before the given time
+fn get_playback_frame(frames: &[ReplayFrame], time: f32) -> Option<&ReplayFrame> {
+    frames
+        .iter()
+        .take_while(|f| f.time <= time)
+        .last()
+        .or_else(|| frames.first())
+}
+
+fn play_replay(
+    mut commands: Commands,
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut ghost_q: Query<(Entity, &ReplayGhost, &mut Transform)>,
+    asset_server: Res<AssetServer>,
+    time: GameTime,
+) {
+    let mut playback = match playback {
+        Some(playback) => playback,
+        None => return,
+    };
+
+    playback.time += time.scaled_delta_seconds();
+    if let Some(last) = playback.data.frames.last() {
+        if playback.time > last.time {
+            // loop
+            playback.time = 0.;
+        }
+    }
+
+    let frame = match get_playback_frame(&playback.data.frames, playback.time) {
+        Some(frame) => frame,
+        None => return,
+    };
+    let ghosts: Vec<_> = frame
+        .balls
+        .iter()
+        .enumerate()
+        .map(|(i, pos)| (ReplayGhost::Ball(i), *pos))
+        .chain(
+            frame
+                .players
+                .iter()
+                .map(|(id, pos)| (ReplayGhost::Player(*id), *pos)),
+        )
+        .collect();
+
+    for (e, ghost, mut t) in ghost_q.iter_mut() {
+        match ghosts.iter().find(|(g, _)| g == ghost) {
+            Some((_, pos)) => t.translation = Vec3::from(*pos),
+            // e.g. the ball got used up
+            None => commands.entity(e).despawn_recursive(),
+        }
+    }
+
+    for (ghost, pos) in ghosts.iter() {
+        if ghost_q.iter().any(|(_, g, _)| g == ghost) {
+            continue;
+        }
+
+        let texture = match ghost {
+            ReplayGhost::Ball(_) => "art-ish/ball.png",
+            ReplayGhost::Player(_) => "art-ish/player_body.png",
+        };
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: asset_server.load(texture),
+                sprite: Sprite {
+                    color: Color::rgba(1., 1., 1., 0.35),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(Vec3::from(*pos)),
+                ..Default::default()
+            })
+            .insert(*ghost)
+            .insert(Name::new("ReplayGhost"));
+    }
+}
+
+fn despawn_ghosts(mut commands: Commands, ghost_q: Query<Entity, With<ReplayGhost>>) {
+    for e in ghost_q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn export_replay(input: Res<PlayerInput>, recorder: Res<ReplayRecorder>, config: Res<MatchConfig>) {
+    for id in 1..=4 {
+        if input.just_pressed(id, InputAction::ExportReplay) {
+            let data = ReplayData {
+                version: REPLAY_VERSION,
+                seed: config.seed,
+                frames: recorder.frames.clone(),
+            };
+
+            let path = get_export_path();
+
+            match save_replay(&path, &data) {
+                Ok(_) => info!("Replay saved to {}", path.display()),
+                Err(e) => error!("Failed to save replay: {}", e),
+            }
+
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_data(version: u32) -> ReplayData {
+        ReplayData {
+            version,
+            seed: 42,
+            frames: vec![ReplayFrame {
+                time: 0.5,
+                balls: vec![[10., 20., 0.]],
+                players: vec![(1, [-100., 50., 0.]), (2, [100., -50., 0.])],
+                events: vec![
+                    ReplayEvent::Hit {
+                        player_id: 1,
+                        strength: 0.75,
+                    },
+                    ReplayEvent::Bounce { bounce_count: 1 },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn replay_round_trip() {
+        let path = std::env::temp_dir().join("tennis_rounds_test/round_trip.ron");
+        save_replay(&path, &get_test_data(REPLAY_VERSION)).unwrap();
+        let data = load_replay(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(data.seed, 42);
+        assert_eq!(data.frames.len(), 1);
+        let frame = &data.frames[0];
+        assert_eq!(frame.time, 0.5);
+        assert_eq!(frame.balls, vec![[10., 20., 0.]]);
+        assert_eq!(frame.players[1], (2, [100., -50., 0.]));
+        assert!(matches!(
+            frame.events[..],
+            [
+                ReplayEvent::Hit { player_id: 1, .. },
+                ReplayEvent::Bounce { bounce_count: 1 }
+            ]
+        ));
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let path = std::env::temp_dir().join("tennis_rounds_test/old_version.ron");
+        save_replay(&path, &get_test_data(REPLAY_VERSION + 1)).unwrap();
+        let res = load_replay(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn playback_picks_the_last_frame_so_far() {
+        let frames: Vec<_> = [0.1, 0.2, 0.3]
+            .iter()
+            .map(|time| ReplayFrame {
+                time: *time,
+                ..Default::default()
+            })
+            .collect();
+
+        assert_eq!(get_playback_frame(&frames, 0.).unwrap().time, 0.1);
+        assert_eq!(get_playback_frame(&frames, 0.25).unwrap().time, 0.2);
+        assert_eq!(get_playback_frame(&frames, 1.).unwrap().time, 0.3);
+        assert!(get_playback_frame(&[], 0.).is_none());
+    }
+}
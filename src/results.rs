@@ -0,0 +1,258 @@
+use crate::{
+    input_binding::{InputAction, InputAxis, PlayerInput},
+    match_config::{GameMode, MatchConfig},
+    palette::PaletteColor,
+    reset::Persistent,
+    score::{GameOverEvt, Score},
+    GameState,
+};
+use bevy::prelude::*;
+
+const GAME_OVER_LABEL: &str = "results_game_over";
+
+pub struct ResultsPlugin;
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<QuickRematch>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(on_game_over.label(GAME_OVER_LABEL))
+                    .with_system(show_results.after(GAME_OVER_LABEL)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::Results)
+                    .with_system(despawn_gameplay)
+                    .with_system(setup_ui),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Results)
+                    .with_system(handle_results_input)
+                    .with_system(update_ui),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Results).with_system(cleanup_ui));
+    }
+}
+
+pub struct MatchResult {
+    pub winner_id: usize,
+    pub left_games: u8,
+    pub right_games: u8,
+    pub left_volleys: u16,
+    pub right_volleys: u16,
+    // shown so the matchup can be shared & replayed with `--seed`
+    // nice2have: copy to clipboard
+    pub seed: u64,
+    selected: usize,
+    axis_released: bool,
+    // the results wait for any other queued transition (e.g. a hawk-eye review)
+    shown: bool,
+}
+
+// skips the warm-up & the first countdown, stays set for resets until the next regular start
+#[derive(Default)]
+pub struct QuickRematch(pub bool);
+
+const OPTIONS: [&str; 3] = ["Rematch", "Quick rematch", "Menu"];
+
+#[derive(Component)]
+struct ResultsText;
+
+fn on_game_over(
+    mut commands: Commands,
+    mut ev_r_game_over: EventReader<GameOverEvt>,
+    result: Option<Res<MatchResult>>,
+    score: Res<Score>,
+    config: Res<MatchConfig>,
+    game_mode: Res<GameMode>,
+) {
+    // the attract demo goes straight back to the menu
+    if result.is_some() || *game_mode == GameMode::Attract {
+        return;
+    }
+
+    if let Some(ev) = ev_r_game_over.iter().next() {
+        commands.insert_resource(MatchResult {
+            winner_id: ev.winner_id,
+            left_games: score.left_player.games,
+            right_games: score.right_player.games,
+            left_volleys: score.left_player.volleys,
+            right_volleys: score.right_player.volleys,
+            seed: config.seed,
+            selected: 0,
+            axis_released: false,
+            shown: false,
+        });
+    }
+}
+
+fn show_results(result: Option<ResMut<MatchResult>>, mut state: ResMut<State<GameState>>) {
+    if let Some(mut result) = result {
+        if result.shown {
+            return;
+        }
+
+        match state.set(GameState::Results) {
+            Ok(_) => result.shown = true,
+            // retried next frame
+            Err(e) => debug!("Results postponed: {:?}", e),
+        }
+    }
+}
+
+fn despawn_gameplay(
+    mut commands: Commands,
+    despawn_q: Query<Entity, (Without<Persistent>, Without<Parent>)>,
+) {
+    for e in despawn_q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                margin: Rect::all(Val::Auto),
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(ResultsText)
+        .insert(Name::new("ResultsText"));
+}
+
+fn handle_results_input(
+    mut input: ResMut<PlayerInput>,
+    result: Option<ResMut<MatchResult>>,
+    mut state: ResMut<State<GameState>>,
+    mut quick_rematch: ResMut<QuickRematch>,
+) {
+    let mut result = match result {
+        Some(result) => result,
+        None => return,
+    };
+
+    for id in 1..=4 {
+        let x = input
+            .get_xy_axes_raw(id, &InputAxis::MoveX, &InputAxis::MoveY)
+            .x;
+
+        if x.abs() >= 0.5 {
+            if result.axis_released {
+                result.axis_released = false;
+                result.selected = if x < 0. {
+                    result.selected.saturating_sub(1)
+                } else {
+                    (result.selected + 1).min(OPTIONS.len() - 1)
+                };
+            }
+
+            return;
+        }
+
+        if input.just_pressed(id, InputAction::Swing) {
+            input.use_button_action(id, InputAction::Swing);
+
+            let next_state = if result.selected < 2 {
+                GameState::Game
+            } else {
+                GameState::Menu
+            };
+
+            match state.set(next_state) {
+                // MatchConfig is left untouched, so a rematch keeps the same rules
+                Ok(_) => quick_rematch.0 = result.selected == 1,
+                Err(e) => warn!("Failed to leave the results: {:?}", e),
+            }
+
+            return;
+        }
+    }
+
+    result.axis_released = true;
+}
+
+fn update_ui(result: Option<Res<MatchResult>>, mut text_q: Query<&mut Text, With<ResultsText>>) {
+    let result = match result {
+        Some(result) => result,
+        None => return,
+    };
+    let options: Vec<String> = OPTIONS
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            if i == result.selected {
+                format!("[{}]", option)
+            } else {
+                option.to_string()
+            }
+        })
+        .collect();
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = format!(
+            "Player {} wins!\n{} : {}\nVolleys {} : {}\nSeed: {}\n\n{}",
+            result.winner_id,
+            result.left_games,
+            result.right_games,
+            result.left_volleys,
+            result.right_volleys,
+            result.seed,
+            options.join("   ")
+        );
+    }
+}
+
+fn cleanup_ui(mut commands: Commands, text_q: Query<Entity, With<ResultsText>>) {
+    for e in text_q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+
+    commands.remove_resource::<MatchResult>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_wait_for_queued_transition() {
+        let mut app = App::new();
+        app.insert_resource(State::new(GameState::Game))
+            .insert_resource(MatchResult {
+                winner_id: 1,
+                left_games: 3,
+                right_games: 1,
+                left_volleys: 0,
+                right_volleys: 0,
+                seed: 0,
+                selected: 0,
+                axis_released: false,
+                shown: false,
+            })
+            .add_system(show_results);
+        // e.g. a hawk-eye review on the same frame
+        app.world
+            .get_resource_mut::<State<GameState>>()
+            .unwrap()
+            .push(GameState::HawkEye)
+            .unwrap();
+        app.update();
+
+        assert!(!app.world.get_resource::<MatchResult>().unwrap().shown);
+    }
+}
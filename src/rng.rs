@@ -0,0 +1,26 @@
+use crate::{match_config::MatchConfig, GameSetupPhase, GameState};
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+pub struct RngPlugin;
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<GameRng>().add_system_set(
+            SystemSet::on_enter(GameState::Game).with_system(seed_rng.before(GameSetupPhase::Ball)),
+        );
+    }
+}
+
+// all gameplay randomness goes through this so a match can be replayed from its seed
+pub struct GameRng(pub StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::seed_from_u64(0))
+    }
+}
+
+fn seed_rng(mut rng: ResMut<GameRng>, config: Res<MatchConfig>) {
+    info!("Match seed: {}", config.seed);
+    rng.0 = StdRng::seed_from_u64(config.seed);
+}
@@ -0,0 +1,146 @@
+use crate::{ball::BallHitEvt, input_binding::get_player_gamepad_id, match_config::GameMode};
+use bevy::prelude::*;
+use gilrs::{
+    ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Repeat, Replay, Ticks},
+    Gilrs,
+};
+
+pub struct RumblePlugin;
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<RumbleSettings>()
+            .insert_non_send_resource(ActiveRumbles::default())
+            .add_event::<RumbleEvt>()
+            .add_system(rumble_on_hit)
+            .add_system(play_rumble)
+            .add_system(cleanup_rumbles);
+    }
+}
+
+pub struct RumbleSettings {
+    pub enabled: bool,
+    pub intensity: f32,
+}
+
+impl Default for RumbleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum RumbleKind {
+    SoftHit,
+    StrongHit,
+    Fault,
+    PointLost,
+}
+
+impl RumbleKind {
+    // (magnitude, pulse duration, pulse count)
+    fn get_params(&self) -> (f32, u64, u64) {
+        match self {
+            RumbleKind::SoftHit => (0.3, 80, 1),
+            RumbleKind::StrongHit => (1., 150, 1),
+            RumbleKind::Fault => (0.5, 120, 1),
+            RumbleKind::PointLost => (0.8, 120, 2),
+        }
+    }
+}
+
+pub struct RumbleEvt {
+    pub player_id: usize,
+    pub kind: RumbleKind,
+}
+
+// effects stop once dropped, so they're kept around until they're done playing
+#[derive(Default)]
+struct ActiveRumbles(Vec<(Effect, Timer)>);
+
+fn rumble_on_hit(mut ev_r_hit: EventReader<BallHitEvt>, mut ev_w_rumble: EventWriter<RumbleEvt>) {
+    for ev in ev_r_hit.iter() {
+        ev_w_rumble.send(RumbleEvt {
+            player_id: ev.player_id,
+            kind: if ev.strength >= 0.75 {
+                RumbleKind::StrongHit
+            } else {
+                RumbleKind::SoftHit
+            },
+        });
+    }
+}
+
+fn play_rumble(
+    mut ev_r_rumble: EventReader<RumbleEvt>,
+    settings: Res<RumbleSettings>,
+    game_mode: Res<GameMode>,
+    gilrs: Option<NonSendMut<Gilrs>>,
+    mut active: NonSendMut<ActiveRumbles>,
+) {
+    let mut gilrs = match gilrs {
+        Some(gilrs) if settings.enabled && settings.intensity > 0. => gilrs,
+        _ => return,
+    };
+
+    // AI players have no pad, even if someone's holding the one with their id
+    for ev in ev_r_rumble
+        .iter()
+        .filter(|ev| !game_mode.is_ai_player(ev.player_id))
+    {
+        let gamepad_id = get_player_gamepad_id(ev.player_id);
+        let gilrs_id = gilrs
+            .gamepads()
+            .find(|(id, gamepad)| usize::from(*id) == gamepad_id && gamepad.is_ff_supported())
+            .map(|(id, _)| id);
+
+        if let Some(gilrs_id) = gilrs_id {
+            let (magnitude, duration_ms, pulses) = ev.kind.get_params();
+            let magnitude = (magnitude * settings.intensity).clamp(0., 1.);
+            let mut builder = EffectBuilder::new();
+
+            for i in 0..pulses {
+                builder.add_effect(BaseEffect {
+                    kind: BaseEffectType::Strong {
+                        magnitude: (magnitude * u16::MAX as f32) as u16,
+                    },
+                    scheduling: Replay {
+                        after: Ticks::from_ms((i * duration_ms * 2) as u32),
+                        play_for: Ticks::from_ms(duration_ms as u32),
+                        with_delay: Ticks::from_ms(0),
+                    },
+                    ..Default::default()
+                });
+            }
+
+            let total_ms = duration_ms * (pulses * 2 - 1);
+            match builder
+                .repeat(Repeat::For(Ticks::from_ms(total_ms as u32)))
+                .gamepads(&[gilrs_id])
+                .finish(&mut gilrs)
+            {
+                Ok(effect) => {
+                    if let Err(e) = effect.play() {
+                        warn!("Failed to play rumble: {}", e);
+                    } else {
+                        trace!("Rumble {:?} for player {}", ev.kind, ev.player_id);
+                        active
+                            .0
+                            .push((effect, Timer::from_seconds(total_ms as f32 / 1000., false)));
+                    }
+                }
+                Err(e) => warn!("Failed to build rumble effect: {}", e),
+            }
+        }
+    }
+}
+
+fn cleanup_rumbles(mut active: NonSendMut<ActiveRumbles>, time: Res<Time>) {
+    for (_, timer) in active.0.iter_mut() {
+        timer.tick(time.delta());
+    }
+
+    active.0.retain(|(_, timer)| !timer.finished());
+}
@@ -1,6 +1,15 @@
-use crate::{palette::PaletteColor, reset::Persistent, GameState};
+use crate::{
+    ball::BallHitEvt,
+    match_config::{DeuceRule, MatchConfig, ScoringMode, ServeRotation},
+    palette::{Palette, PaletteColor},
+    player::is_left_player_id,
+    reset::Persistent,
+    GameState,
+};
 use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
+use bevy_tweening::{lens::TextColorLens, Animator, EaseFunction, Tween, TweeningType};
+use std::time::Duration;
 
 pub struct ScorePlugin;
 impl Plugin for ScorePlugin {
@@ -8,17 +17,28 @@ impl Plugin for ScorePlugin {
         app.init_resource::<Score>()
             .add_startup_system(setup)
             .add_system_set(SystemSet::on_enter(GameState::Game).with_system(reset_score))
-            .add_system(update_score_ui);
+            .add_system(count_volleys)
+            .add_system(update_score_ui)
+            .add_system(highlight_golden_point)
+            .add_event::<GoldenPointEvt>()
+            .add_event::<GameWonEvt>()
+            .add_event::<GameOverEvt>();
     }
 }
 
+// each side's points sit on their side of the court in the side's accent
 #[derive(Component, Inspectable)]
-struct PointsText;
+struct PointsText {
+    is_left: bool,
+}
 
 #[derive(Default)]
 pub struct Score {
     pub left_player: PlayerScore,
     pub right_player: PlayerScore,
+    // in the current game (the whole match in quick play)
+    // unlike the displayed points it isn't capped at deuce, so it's safe for serve parity
+    pub points_played: u32,
 }
 
 #[derive(Default, Component, Inspectable)]
@@ -26,14 +46,61 @@ pub struct PlayerScore {
     pub points: u8,
     pub games: u8,
     // pub sets: u8,
+    // match stat, shown in the results
+    pub volleys: u16,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointOutcome {
+    Point,
+    GameWon,
+    MatchWon,
+}
+
+pub struct GoldenPointEvt {
+    pub serving_player_id: usize,
+}
+
+pub struct GameWonEvt {
+    pub winner_id: usize,
+    pub loser_id: usize,
+}
+
+pub struct GameOverEvt {
+    pub winner_id: usize,
+    pub loser_id: usize,
 }
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/Typo_Round_Regular_Demo.otf");
+    let text_bundle = |value: &str, horizontal: HorizontalAlign| TextBundle {
+        style: Style {
+            // fixed width so the layout doesn't jump with the digit count
+            size: Size::new(Val::Px(120.), Val::Auto),
+            ..Default::default()
+        },
+        text: Text::with_section(
+            value,
+            TextStyle {
+                font: font.clone(),
+                font_size: 100.0,
+                color: Color::WHITE,
+            },
+            TextAlignment {
+                horizontal,
+                ..Default::default()
+            },
+        ),
+        ..Default::default()
+    };
+
     commands
-        .spawn_bundle(TextBundle {
+        .spawn_bundle(NodeBundle {
             style: Style {
                 align_self: AlignSelf::Center,
                 position_type: PositionType::Relative,
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
                 margin: Rect {
                     top: Val::Auto,
                     bottom: Val::Px(10.0),
@@ -42,37 +109,44 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 },
                 ..Default::default()
             },
-            text: Text::with_section(
-                "",
-                TextStyle {
-                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
-                    font_size: 100.0,
-                    color: Color::WHITE,
-                },
-                // Note: You can use `Default::default()` in place of the `TextAlignment`
-                TextAlignment {
-                    horizontal: HorizontalAlign::Center,
-                    ..Default::default()
-                },
-            ),
+            color: Color::NONE.into(),
             ..Default::default()
         })
-        .insert(PaletteColor::Text)
-        .insert(PointsText)
         .insert(Name::new("ScoreText"))
-        .insert(Persistent);
+        .insert(Persistent)
+        .with_children(|b| {
+            // accents are looked up by id - 1 starts on the left, 2 on the right
+            b.spawn_bundle(text_bundle("", HorizontalAlign::Right))
+                .insert(PaletteColor::PlayerAccent(1))
+                .insert(PointsText { is_left: true });
+            b.spawn_bundle(text_bundle("|", HorizontalAlign::Center))
+                .insert(PaletteColor::Text);
+            b.spawn_bundle(text_bundle("", HorizontalAlign::Left))
+                .insert(PaletteColor::PlayerAccent(2))
+                .insert(PointsText { is_left: false });
+        });
 }
 
-fn update_score_ui(score: Res<Score>, mut points_text_q: Query<&mut Text, With<PointsText>>) {
+fn update_score_ui(score: Res<Score>, mut points_text_q: Query<(&mut Text, &PointsText)>) {
     if score.is_changed() {
-        points_text_q.single_mut().sections[0].value = format!(
-            "{} | {}",
-            score.left_player.points, score.right_player.points
-        );
+        for (mut text, points_text) in points_text_q.iter_mut() {
+            let player_score = if points_text.is_left {
+                &score.left_player
+            } else {
+                &score.right_player
+            };
+            text.sections[0].value = player_score.points.to_string();
+        }
     }
 }
 
-pub fn add_point_to_score(score: &mut Score, add_to_left_player: bool) -> bool {
+pub fn add_point_to_score(
+    score: &mut Score,
+    add_to_left_player: bool,
+    config: &MatchConfig,
+) -> PointOutcome {
+    score.points_played += 1;
+
     let (mut scoring, mut other) = if add_to_left_player {
         (&mut score.left_player, &mut score.right_player)
     } else {
@@ -81,7 +155,19 @@ pub fn add_point_to_score(score: &mut Score, add_to_left_player: bool) -> bool {
 
     scoring.points += 1;
 
-    let mut required_points = (other.points + 2).max(4);
+    if let ScoringMode::FirstTo(target) = config.scoring_mode {
+        let lead_reached = !config.win_by_two || scoring.points >= other.points + 2;
+        return if scoring.points as u32 >= target && lead_reached {
+            PointOutcome::MatchWon
+        } else {
+            PointOutcome::Point
+        };
+    }
+
+    let mut required_points = match config.deuce_rule {
+        DeuceRule::Advantage => (other.points + 2).max(4),
+        DeuceRule::GoldenPoint => 4,
+    };
     if cfg!(feature = "debug") {
         required_points = 100;
     }
@@ -90,7 +176,13 @@ pub fn add_point_to_score(score: &mut Score, add_to_left_player: bool) -> bool {
         scoring.games += 1;
         scoring.points = 0;
         other.points = 0;
-        return true;
+        score.points_played = 0;
+
+        return if scoring.games >= config.games_to_win {
+            PointOutcome::MatchWon
+        } else {
+            PointOutcome::GameWon
+        };
     } else if scoring.points == other.points && scoring.points > 3 {
         // hacky way to get ADV in the UI
         // nice2have: redo
@@ -102,10 +194,167 @@ pub fn add_point_to_score(score: &mut Score, add_to_left_player: bool) -> bool {
     // if scoring.games >= 6 {
     // }
 
-    false
+    PointOutcome::Point
+}
+
+// called with the point already added to the score
+pub fn should_swap_serve(score: &Score, outcome: PointOutcome, config: &MatchConfig) -> bool {
+    let game_end = outcome != PointOutcome::Point;
+    let points = score.points_played;
+
+    match (config.serve_rotation, config.scoring_mode) {
+        (ServeRotation::Auto, ScoringMode::Tennis) | (ServeRotation::EveryGame, _) => game_end,
+        (ServeRotation::Auto, ScoringMode::FirstTo(_)) => points % 2 == 0,
+        (ServeRotation::EveryPoint, _) => true,
+        (ServeRotation::Tiebreak, _) => game_end || points % 2 == 1,
+    }
+}
+
+pub fn is_golden_point(score: &Score, config: &MatchConfig) -> bool {
+    config.scoring_mode == ScoringMode::Tennis
+        && config.deuce_rule == DeuceRule::GoldenPoint
+        && score.left_player.points == 3
+        && score.right_player.points == 3
+}
+
+fn highlight_golden_point(
+    mut commands: Commands,
+    mut ev_r_golden_point: EventReader<GoldenPointEvt>,
+    points_text_q: Query<(Entity, &PaletteColor), With<PointsText>>,
+    palette: Res<Palette>,
+) {
+    for _ev in ev_r_golden_point.iter() {
+        for (e, col) in points_text_q.iter() {
+            commands.entity(e).insert(Animator::new(Tween::new(
+                EaseFunction::QuadraticIn,
+                TweeningType::Once,
+                Duration::from_millis(1500),
+                TextColorLens {
+                    start: palette.get_color(&PaletteColor::Ball),
+                    end: palette.get_color(col),
+                    section: 0,
+                },
+            )));
+        }
+    }
+}
+
+fn count_volleys(mut ev_r_hit: EventReader<BallHitEvt>, mut score: ResMut<Score>) {
+    for ev in ev_r_hit.iter().filter(|ev| ev.volley) {
+        if is_left_player_id(ev.player_id) {
+            score.left_player.volleys += 1;
+        } else {
+            score.right_player.volleys += 1;
+        }
+    }
 }
 
 fn reset_score(mut score: ResMut<Score>) {
     score.left_player = PlayerScore::default();
     score.right_player = PlayerScore::default();
+    score.points_played = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::Events;
+
+    fn get_config(serve_rotation: ServeRotation, scoring_mode: ScoringMode) -> MatchConfig {
+        MatchConfig {
+            serve_rotation,
+            scoring_mode,
+            deuce_rule: DeuceRule::Advantage,
+            ..Default::default()
+        }
+    }
+
+    // alternates the point winner, returns whether the serve swapped after each point
+    fn play_points(config: &MatchConfig, count: usize) -> Vec<bool> {
+        let mut score = Score::default();
+        (0..count)
+            .map(|i| {
+                let outcome = add_point_to_score(&mut score, i % 2 == 0, config);
+                should_swap_serve(&score, outcome, config)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_point_always_swaps() {
+        let config = get_config(ServeRotation::EveryPoint, ScoringMode::Tennis);
+        assert!(play_points(&config, 5).into_iter().all(|swap| swap));
+        assert!(should_swap_serve(
+            &Score::default(),
+            PointOutcome::GameWon,
+            &config
+        ));
+    }
+
+    #[test]
+    fn every_game_swaps_on_game_end_only() {
+        for mode in [ScoringMode::Tennis, ScoringMode::FirstTo(11)] {
+            let config = get_config(ServeRotation::EveryGame, mode);
+            let score = Score::default();
+            assert!(!should_swap_serve(&score, PointOutcome::Point, &config));
+            assert!(should_swap_serve(&score, PointOutcome::GameWon, &config));
+            assert!(should_swap_serve(&score, PointOutcome::MatchWon, &config));
+        }
+    }
+
+    #[test]
+    fn tiebreak_first_server_serves_once_then_every_two() {
+        let config = get_config(ServeRotation::Tiebreak, ScoringMode::FirstTo(21));
+        assert_eq!(
+            play_points(&config, 6),
+            vec![true, false, true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn tiebreak_parity_holds_past_deuce() {
+        let config = get_config(ServeRotation::Tiebreak, ScoringMode::Tennis);
+        // deuce & ADV back and forth, the displayed points get capped at 3-3
+        let swaps = play_points(&config, 12);
+        let expected: Vec<bool> = (1..=12).map(|played| played % 2 == 1).collect();
+        assert_eq!(swaps, expected);
+    }
+
+    #[test]
+    fn game_end_resets_points_played() {
+        let config = get_config(ServeRotation::Tiebreak, ScoringMode::Tennis);
+        let mut score = Score::default();
+        let mut outcome = PointOutcome::Point;
+
+        while outcome == PointOutcome::Point {
+            outcome = add_point_to_score(&mut score, true, &config);
+        }
+
+        assert_eq!(score.points_played, 0);
+        assert!(should_swap_serve(&score, outcome, &config));
+    }
+
+    #[test]
+    fn only_volleys_are_counted_per_side() {
+        let mut app = App::new();
+        app.add_event::<BallHitEvt>()
+            .init_resource::<Score>()
+            .add_system(count_volleys);
+
+        let mut hits = app.world.get_resource_mut::<Events<BallHitEvt>>().unwrap();
+        for (player_id, volley) in [(1, true), (2, false), (3, true), (2, true)] {
+            hits.send(BallHitEvt {
+                ball_e: Entity::from_raw(0),
+                player_id,
+                strength: 1.,
+                power_shot: false,
+                volley,
+            });
+        }
+        app.update();
+
+        let score = app.world.get_resource::<Score>().unwrap();
+        assert_eq!(score.left_player.volleys, 2);
+        assert_eq!(score.right_player.volleys, 1);
+    }
 }
@@ -0,0 +1,300 @@
+use crate::{
+    animation::TweenDoneAction,
+    ball::{Ball, BallStatus, CurrentBall},
+    ends::Ends,
+    game_speed::GameTime,
+    level::ServingRegion,
+    palette::{Palette, PaletteColor},
+    player::Player,
+    render::BALL_Z,
+    reset::Persistent,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::{SpriteColorLens, TextColorLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use std::time::Duration;
+
+pub struct ServePlugin;
+impl Plugin for ServePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<ServeChangedEvt>()
+            .init_resource::<ServePositionSettings>()
+            .add_startup_system(setup)
+            .add_system(update_serve_indicator)
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(announce_serve_change)
+                    .with_system(spawn_server_marker)
+                    .with_system(fade_server_marker)
+                    .with_system(start_serve_approach)
+                    .with_system(approach_serve),
+            );
+    }
+}
+
+// nice2have: consume in an audio system for a voice announcement
+pub struct ServeChangedEvt {
+    pub serving_player_id: usize,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ServePositioning {
+    #[default]
+    Off,
+    Walk,
+    Snap,
+}
+
+// moves the server behind the freshly spawned serve ball
+pub struct ServePositionSettings {
+    pub mode: ServePositioning,
+    pub walk_speed: f32,
+    // distance behind the ball, away from the net
+    pub offset: f32,
+}
+
+impl Default for ServePositionSettings {
+    fn default() -> Self {
+        Self {
+            mode: ServePositioning::default(),
+            walk_speed: 350.,
+            offset: 50.,
+        }
+    }
+}
+
+// the server is being moved into place, their movement input is ignored meanwhile
+#[derive(Component)]
+pub struct ServeApproach {
+    target: Vec2,
+}
+
+#[derive(Component)]
+struct ServeIndicator;
+
+// arrow over whoever is up to serve, the service box highlight shows where
+#[derive(Component)]
+struct ServerMarker;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::FlexEnd,
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(20.),
+                    right: Val::Px(20.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(PaletteColor::Text)
+        .insert(ServeIndicator)
+        .insert(Name::new("ServeIndicator"))
+        .insert(Persistent);
+}
+
+fn update_serve_indicator(
+    serving_region: Option<Res<ServingRegion>>,
+    mut text_q: Query<&mut Text, With<ServeIndicator>>,
+    ends: Res<Ends>,
+) {
+    if let Some(serving_region) = serving_region {
+        if serving_region.is_changed() {
+            for mut text in text_q.iter_mut() {
+                text.sections[0].value = format!(
+                    "P{} serving",
+                    ends.get_player_id(serving_region.0.is_left())
+                );
+            }
+        }
+    }
+}
+
+fn announce_serve_change(
+    mut commands: Commands,
+    mut ev_r_serve_changed: EventReader<ServeChangedEvt>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+) {
+    for ev in ev_r_serve_changed.iter() {
+        let color = palette.get_color(&PaletteColor::PlayerAccent(ev.serving_player_id));
+
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(
+                    format!("P{} TO SERVE", ev.serving_player_id),
+                    TextStyle {
+                        font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                        font_size: 50.,
+                        color,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        vertical: VerticalAlign::Center,
+                    },
+                ),
+                transform: Transform {
+                    translation: Vec2::new(0., 200.).extend(BALL_Z + 1.),
+                    scale: Vec2::splat(0.5).extend(1.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Animator::new(Tween::new(
+                EaseFunction::BackOut,
+                TweeningType::Once,
+                Duration::from_millis(250),
+                TransformScaleLens {
+                    start: Vec2::splat(0.5).extend(1.),
+                    end: Vec3::ONE,
+                },
+            )))
+            .insert(Animator::new(
+                Tween::new(
+                    EaseFunction::QuadraticIn,
+                    TweeningType::Once,
+                    Duration::from_millis(1200),
+                    TextColorLens {
+                        start: color,
+                        end: Color::NONE,
+                        section: 0,
+                    },
+                )
+                .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+            ))
+            .insert(Name::new("ServeAnnouncement"));
+    }
+}
+
+fn spawn_server_marker(
+    mut commands: Commands,
+    ball_q: Query<&BallStatus, Added<Ball>>,
+    player_q: Query<(Entity, &Player)>,
+    serving_region: Option<Res<ServingRegion>>,
+    ends: Res<Ends>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+) {
+    let serving_region = match serving_region {
+        Some(serving_region) => serving_region,
+        None => return,
+    };
+
+    if !ball_q
+        .iter()
+        .any(|status| matches!(status, BallStatus::Serve(..)))
+    {
+        return;
+    }
+
+    let server_id = ends.get_player_id(serving_region.0.is_left());
+
+    if let Some((player_e, _)) = player_q.iter().find(|(_, p)| p.id == server_id) {
+        commands.entity(player_e).with_children(|b| {
+            b.spawn_bundle(SpriteBundle {
+                texture: asset_server.load("art-ish/aim_arrow.png"),
+                sprite: Sprite {
+                    color: palette.get_color(&PaletteColor::PlayerAccent(server_id)),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: Vec3::new(0., 90., 1.),
+                    rotation: Quat::from_rotation_z(std::f32::consts::PI),
+                    scale: Vec2::splat(0.5).extend(1.),
+                },
+                ..Default::default()
+            })
+            .insert(ServerMarker)
+            .insert(Name::new("ServerMarker"));
+        });
+    }
+}
+
+// fades once the serve is struck (or the ball is gone)
+fn fade_server_marker(
+    mut commands: Commands,
+    marker_q: Query<(Entity, &Sprite), (With<ServerMarker>, Without<Animator<Sprite>>)>,
+    current_ball: Res<CurrentBall>,
+) {
+    if current_ball.is_serving() {
+        return;
+    }
+
+    for (e, sprite) in marker_q.iter() {
+        commands.entity(e).insert(Animator::new(
+            Tween::new(
+                EaseFunction::QuadraticIn,
+                TweeningType::Once,
+                Duration::from_millis(300),
+                SpriteColorLens {
+                    start: sprite.color,
+                    end: Color::NONE,
+                },
+            )
+            .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+        ));
+    }
+}
+
+fn start_serve_approach(
+    mut commands: Commands,
+    ball_q: Query<(&BallStatus, &Transform), Added<Ball>>,
+    mut player_q: Query<(Entity, &Player, &mut Transform), Without<Ball>>,
+    settings: Res<ServePositionSettings>,
+) {
+    if settings.mode == ServePositioning::Off {
+        return;
+    }
+
+    for (status, ball_t) in ball_q.iter() {
+        if let BallStatus::Serve(_, _, player_id) = *status {
+            if let Some((e, player, mut player_t)) =
+                player_q.iter_mut().find(|(_, p, _)| p.id == player_id)
+            {
+                let target =
+                    ball_t.translation.truncate() + Vec2::X * player.get_sign() * settings.offset;
+
+                if settings.mode == ServePositioning::Snap {
+                    player_t.translation = target.extend(player_t.translation.z);
+                } else {
+                    commands.entity(e).insert(ServeApproach { target });
+                }
+            }
+        }
+    }
+}
+
+fn approach_serve(
+    mut commands: Commands,
+    mut player_q: Query<(Entity, &ServeApproach, &mut Transform)>,
+    settings: Res<ServePositionSettings>,
+    time: GameTime,
+) {
+    for (e, approach, mut player_t) in player_q.iter_mut() {
+        let to_target = approach.target - player_t.translation.truncate();
+        let step = settings.walk_speed * time.scaled_delta_seconds();
+
+        if to_target.length() <= step {
+            player_t.translation = approach.target.extend(player_t.translation.z);
+            commands.entity(e).remove::<ServeApproach>();
+        } else {
+            player_t.translation += (to_target.normalize() * step).extend(0.);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::ball::BallHitEvt;
+use bevy::prelude::*;
+
+pub struct SfxPlugin;
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<HitSoundSettings>()
+            .add_event::<HitSoundEvt>()
+            .add_system(on_ball_hit)
+            .add_system(play_hit_sound);
+    }
+}
+
+pub struct HitSoundSettings {
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    pub min_volume: f32,
+    pub max_volume: f32,
+}
+
+impl Default for HitSoundSettings {
+    fn default() -> Self {
+        Self {
+            min_pitch: 0.8,
+            max_pitch: 1.3,
+            min_volume: 0.4,
+            max_volume: 1.,
+        }
+    }
+}
+
+impl HitSoundSettings {
+    // (pitch, volume)
+    pub fn get_playback_params(&self, strength: f32) -> (f32, f32) {
+        let strength = strength.clamp(0., 1.);
+        let pitch = self.min_pitch + (self.max_pitch - self.min_pitch) * strength;
+        let volume = self.min_volume + (self.max_volume - self.min_volume) * strength;
+
+        (pitch.clamp(self.min_pitch, self.max_pitch), volume)
+    }
+}
+
+// playback params for the hit sound
+pub struct HitSoundEvt {
+    pub pitch: f32,
+    pub volume: f32,
+    pub power_shot: bool,
+}
+
+fn on_ball_hit(
+    mut ev_r_hit: EventReader<BallHitEvt>,
+    mut ev_w_hit_sound: EventWriter<HitSoundEvt>,
+    settings: Res<HitSoundSettings>,
+) {
+    for ev in ev_r_hit.iter() {
+        let (pitch, volume) = settings.get_playback_params(ev.strength);
+
+        ev_w_hit_sound.send(HitSoundEvt {
+            pitch,
+            volume,
+            power_shot: ev.power_shot,
+        });
+    }
+}
+
+// nice2have: play an actual sample once there are sound assets & an audio backend with pitch control (bevy_kira_audio?)
+fn play_hit_sound(mut ev_r_hit_sound: EventReader<HitSoundEvt>) {
+    for ev in ev_r_hit_sound.iter() {
+        debug!(
+            "Hit sound - pitch: {:.2}, volume: {:.2}, power shot: {}",
+            ev.pitch, ev.volume, ev.power_shot
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playback_params_scale_with_strength() {
+        let settings = HitSoundSettings::default();
+
+        assert_eq!(
+            settings.get_playback_params(0.),
+            (settings.min_pitch, settings.min_volume)
+        );
+        assert_eq!(
+            settings.get_playback_params(1.),
+            (settings.max_pitch, settings.max_volume)
+        );
+        // overcharged hits are clamped
+        assert_eq!(
+            settings.get_playback_params(2.),
+            settings.get_playback_params(1.)
+        );
+    }
+}
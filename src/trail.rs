@@ -1,6 +1,6 @@
+use crate::game_speed::GameTime;
 use bevy::{math::Vec2, prelude::*};
 use bevy_prototype_lyon::prelude::*;
-use bevy_time::{ScaledTime, ScaledTimeDelta};
 
 pub struct TrailPlugin;
 impl Plugin for TrailPlugin {
@@ -113,7 +113,7 @@ fn draw_trail(mut path_q: Query<(&mut Path, &mut Trail)>, time: Res<Time>) {
     }
 }
 
-fn fadeout_trail(mut path_q: Query<(&FadeOutTrail, &mut Trail)>, time: ScaledTime) {
+fn fadeout_trail(mut path_q: Query<(&FadeOutTrail, &mut Trail)>, time: GameTime) {
     for (fade, mut trail) in path_q.iter_mut() {
         trail.duration_sec =
             (trail.duration_sec - fade.decrease_duration_by * time.scaled_delta_seconds()).max(0.);
@@ -0,0 +1,145 @@
+use crate::{
+    animation::TweenDoneAction,
+    game_speed::GameTime,
+    palette::{Palette, PaletteColor},
+    player::Player,
+    render::BALL_Z,
+    results::QuickRematch,
+    GameSetupPhase, GameState,
+};
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::{TextColorLens, TransformScaleLens},
+    Animator, EaseFunction, Tween, TweeningType,
+};
+use std::time::Duration;
+
+pub struct WarmUpPlugin;
+impl Plugin for WarmUpPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<WarmUpSettings>()
+            .add_event::<WarmUpDoneEvt>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::Game)
+                    .with_system(start_warm_up.after(GameSetupPhase::Player)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(scale_in_players)
+                    .with_system(finish_warm_up),
+            );
+    }
+}
+
+pub struct WarmUpSettings {
+    pub duration_sec: f32,
+}
+
+impl Default for WarmUpSettings {
+    fn default() -> Self {
+        Self { duration_sec: 1.2 }
+    }
+}
+
+// player input is ignored while this exists
+pub struct WarmUp(Timer);
+
+pub struct WarmUpDoneEvt;
+
+fn start_warm_up(
+    mut commands: Commands,
+    settings: Res<WarmUpSettings>,
+    asset_server: Res<AssetServer>,
+    palette: Res<Palette>,
+    quick_rematch: Res<QuickRematch>,
+    mut ev_w_warm_up_done: EventWriter<WarmUpDoneEvt>,
+) {
+    if quick_rematch.0 {
+        ev_w_warm_up_done.send(WarmUpDoneEvt);
+        return;
+    }
+
+    commands.insert_resource(WarmUp(Timer::from_seconds(settings.duration_sec, false)));
+
+    let color = palette.get_color(&PaletteColor::Text);
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "Ready?",
+                TextStyle {
+                    font: asset_server.load("fonts/Typo_Round_Regular_Demo.otf"),
+                    font_size: 80.,
+                    color,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    vertical: VerticalAlign::Center,
+                },
+            ),
+            transform: Transform {
+                translation: Vec3::Z * (BALL_Z + 1.),
+                scale: Vec2::splat(0.5).extend(1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Animator::new(Tween::new(
+            EaseFunction::BackOut,
+            TweeningType::Once,
+            Duration::from_millis(300),
+            TransformScaleLens {
+                start: Vec2::splat(0.5).extend(1.),
+                end: Vec3::ONE,
+            },
+        )))
+        .insert(Animator::new(
+            Tween::new(
+                EaseFunction::ExponentialIn,
+                TweeningType::Once,
+                Duration::from_secs_f32(settings.duration_sec),
+                TextColorLens {
+                    start: color,
+                    end: Color::NONE,
+                    section: 0,
+                },
+            )
+            .with_completed_event(true, TweenDoneAction::DespawnRecursive.into()),
+        ))
+        .insert(Name::new("ReadyText"));
+}
+
+fn scale_in_players(
+    mut commands: Commands,
+    player_q: Query<Entity, Added<Player>>,
+    warm_up: Option<Res<WarmUp>>,
+) {
+    if warm_up.is_none() {
+        return;
+    }
+
+    for e in player_q.iter() {
+        commands.entity(e).insert(Animator::new(Tween::new(
+            EaseFunction::BackOut,
+            TweeningType::Once,
+            Duration::from_millis(500),
+            TransformScaleLens {
+                start: Vec2::splat(0.01).extend(1.),
+                end: Vec3::ONE,
+            },
+        )));
+    }
+}
+
+fn finish_warm_up(
+    mut commands: Commands,
+    warm_up: Option<ResMut<WarmUp>>,
+    mut ev_w_warm_up_done: EventWriter<WarmUpDoneEvt>,
+    time: GameTime,
+) {
+    if let Some(mut warm_up) = warm_up {
+        if warm_up.0.tick(time.scaled_delta()).just_finished() {
+            commands.remove_resource::<WarmUp>();
+            ev_w_warm_up_done.send(WarmUpDoneEvt);
+        }
+    }
+}